@@ -0,0 +1,209 @@
+use std::sync::{Arc, Mutex};
+
+use amethyst::{
+    assets::AssetStorage,
+    audio::{output::Output, AudioSink, Source, SourceHandle},
+    ecs::World,
+};
+
+use super::mixer::{Mixer, Pcm};
+use super::synth::{render_cycles, Blip, Waveform, SAMPLE_RATE};
+use super::table::SoundCue;
+
+#[derive(Clone, Debug)]
+pub enum SoundData {
+    Sample(SourceHandle),
+    Synth(Blip, f32),
+    /// A steady tone made of whole cycles, so segments can be queued back to back on a `ToneSink`.
+    Tone { waveform: Waveform, frequency: f32, duration: f32 },
+}
+
+#[derive(Clone, Debug)]
+pub struct PlayRequest {
+    /// The table cue behind the sound, or `None` for sounds such as the audio guide's.
+    pub cue: Option<SoundCue>,
+    pub sound_id: String,
+    pub data: SoundData,
+    pub volume: f32,
+    pub pan: f32,
+    pub pitch: f32,
+    pub timestamp: f64,
+}
+
+pub trait AudioBackend: Send + Sync {
+    fn play(&mut self, request: &PlayRequest, storage: &AssetStorage<Source>);
+
+    /// Opens a channel for a continuous sound that is fed a segment at a time.
+    fn open_tone(&mut self) -> Box<dyn ToneSink>;
+}
+
+pub trait ToneSink: Send {
+    /// Whether everything queued so far has finished playing.
+    fn is_empty(&self) -> bool;
+    fn set_volume(&mut self, volume: f32);
+    fn queue(&mut self, request: &PlayRequest);
+}
+
+/// The backend every sound goes through, shared by the effect and audio guide systems.
+pub struct SoundOutput(pub Box<dyn AudioBackend>);
+
+impl SoundOutput {
+    /// Records into a `PlayLog` resource when one is present, plays through the audio device when
+    /// there is one, and stays silent otherwise.
+    pub fn setup(world: &mut World) {
+        if world.has_value::<SoundOutput>() {
+            return;
+        }
+
+        let play_log = world.try_fetch::<PlayLog>().map(|play_log| PlayLog::clone(&play_log));
+        let output = world.try_fetch::<Output>().map(|output| Output::clone(&output));
+        let backend: Box<dyn AudioBackend> = match (play_log, output) {
+            (Some(play_log), _) => Box::new(RecordingBackend::new(play_log)),
+            (None, Some(output)) => Box::new(OutputBackend::new(output)),
+            (None, None) => {
+                log::info!("No audio device found, sound is disabled");
+                Box::new(SilentBackend)
+            },
+        };
+        world.insert(SoundOutput(backend));
+    }
+}
+
+fn render_tone(request: &PlayRequest) -> Option<Source> {
+    match &request.data {
+        SoundData::Synth(blip, speed) => Some(Pcm::from_samples(&blip.samples(*speed), SAMPLE_RATE).render(request.pan, request.pitch)),
+        SoundData::Tone { waveform, frequency, duration } => {
+            let samples = render_cycles(*waveform, *frequency, *duration, SAMPLE_RATE);
+            Some(Pcm::from_samples(&samples, SAMPLE_RATE).render(request.pan, request.pitch))
+        },
+        SoundData::Sample(_) => None,
+    }
+}
+
+pub struct OutputBackend {
+    output: Output,
+    mixer: Mixer,
+}
+
+impl OutputBackend {
+    pub fn new(output: Output) -> Self {
+        Self { output, mixer: Mixer::default() }
+    }
+}
+
+impl AudioBackend for OutputBackend {
+    fn play(&mut self, request: &PlayRequest, storage: &AssetStorage<Source>) {
+        match &request.data {
+            SoundData::Sample(handle) => {
+                self.mixer.play(handle, storage, &self.output, request.pan, request.pitch, request.volume);
+            },
+            _ => {
+                if let Some(source) = render_tone(request) {
+                    self.output.play_once(&source, request.volume);
+                }
+            },
+        }
+    }
+
+    fn open_tone(&mut self) -> Box<dyn ToneSink> {
+        Box::new(OutputTone(AudioSink::new(&self.output)))
+    }
+}
+
+struct OutputTone(AudioSink);
+
+impl ToneSink for OutputTone {
+    fn is_empty(&self) -> bool {
+        self.0.empty()
+    }
+
+    fn set_volume(&mut self, volume: f32) {
+        self.0.set_volume(volume);
+    }
+
+    fn queue(&mut self, request: &PlayRequest) {
+        let source = match render_tone(request) {
+            Some(source) => source,
+            None => return,
+        };
+        if let Err(error) = self.0.append(&source) {
+            log::warn!("Could not queue {}: {}", request.sound_id, error);
+        }
+    }
+}
+
+/// Used when there is no audio device, so requests are dropped rather than kept around.
+pub struct SilentBackend;
+
+impl AudioBackend for SilentBackend {
+    fn play(&mut self, _request: &PlayRequest, _storage: &AssetStorage<Source>) {}
+
+    fn open_tone(&mut self) -> Box<dyn ToneSink> {
+        Box::new(SilentBackend)
+    }
+}
+
+impl ToneSink for SilentBackend {
+    fn is_empty(&self) -> bool {
+        true
+    }
+
+    fn set_volume(&mut self, _volume: f32) {}
+
+    fn queue(&mut self, _request: &PlayRequest) {}
+}
+
+/// Every request handed to a `RecordingBackend`. Inserting one into the world before the audio
+/// systems are built makes them record into it instead of playing.
+#[derive(Clone, Default)]
+pub struct PlayLog(pub Arc<Mutex<Vec<PlayRequest>>>);
+
+impl PlayLog {
+    fn record(&self, request: &PlayRequest) {
+        log::debug!(
+            "{:?} would play {} at {:.2} volume ({:.2}s)",
+            request.cue, request.sound_id, request.volume, request.timestamp,
+        );
+
+        if let Ok(mut requests) = self.0.lock() {
+            requests.push(request.clone());
+        }
+    }
+}
+
+pub struct RecordingBackend {
+    log: PlayLog,
+}
+
+impl RecordingBackend {
+    pub fn new(log: PlayLog) -> Self {
+        Self { log }
+    }
+}
+
+impl AudioBackend for RecordingBackend {
+    fn play(&mut self, request: &PlayRequest, _storage: &AssetStorage<Source>) {
+        self.log.record(request);
+    }
+
+    fn open_tone(&mut self) -> Box<dyn ToneSink> {
+        Box::new(RecordingTone { log: self.log.clone() })
+    }
+}
+
+/// Records each queued segment and reports itself drained straight away, as nothing really plays.
+struct RecordingTone {
+    log: PlayLog,
+}
+
+impl ToneSink for RecordingTone {
+    fn is_empty(&self) -> bool {
+        true
+    }
+
+    fn set_volume(&mut self, _volume: f32) {}
+
+    fn queue(&mut self, request: &PlayRequest) {
+        self.log.record(request);
+    }
+}
@@ -0,0 +1,101 @@
+use std::{collections::HashMap, io::Cursor};
+
+use amethyst::{
+    assets::AssetStorage,
+    audio::{output::Output, Source, SourceHandle},
+};
+use rodio::{Decoder, Source as _};
+
+const SYNTH_LEVEL: f32 = 0.4;
+
+pub struct Pcm {
+    pub channels: u16,
+    pub sample_rate: u32,
+    pub samples: Vec<i16>,
+}
+
+impl Pcm {
+    pub fn from_samples(samples: &[f32], sample_rate: u32) -> Pcm {
+        let samples = samples
+            .iter()
+            .map(|sample| (sample.max(-1.0).min(1.0) * SYNTH_LEVEL * f32::from(i16::MAX)) as i16)
+            .collect();
+
+        Pcm { channels: 1, sample_rate, samples }
+    }
+
+    pub fn decode(source: &Source) -> Option<Pcm> {
+        let decoder = Decoder::new(Cursor::new(source.bytes.clone())).ok()?;
+        let channels = decoder.channels();
+        let sample_rate = decoder.sample_rate();
+
+        Some(Pcm { channels, sample_rate, samples: decoder.collect() })
+    }
+
+    /// Folds the sound down to mono and spreads it over two channels, `pan` going from -1 (left) to 1 (right).
+    /// Pitch is shifted by replaying the samples at a scaled rate.
+    pub fn render(&self, pan: f32, pitch: f32) -> Source {
+        let pan = pan.max(-1.0).min(1.0);
+        let left_gain = (1.0 - pan).min(1.0);
+        let right_gain = (1.0 + pan).min(1.0);
+        let channels = self.channels.max(1) as usize;
+
+        let mut stereo = Vec::with_capacity(self.samples.len() / channels * 2);
+        for frame in self.samples.chunks(channels) {
+            let mono = frame.iter().map(|&sample| f32::from(sample)).sum::<f32>() / frame.len() as f32;
+            stereo.push((mono * left_gain) as i16);
+            stereo.push((mono * right_gain) as i16);
+        }
+
+        encode_wav(2, (self.sample_rate as f32 * pitch).max(1.0) as u32, &stereo)
+    }
+}
+
+pub fn encode_wav(channels: u16, sample_rate: u32, samples: &[i16]) -> Source {
+    let data_len = (samples.len() * 2) as u32;
+    let mut bytes = Vec::with_capacity(44 + data_len as usize);
+
+    bytes.extend_from_slice(b"RIFF");
+    bytes.extend_from_slice(&(36 + data_len).to_le_bytes());
+    bytes.extend_from_slice(b"WAVEfmt ");
+    bytes.extend_from_slice(&16u32.to_le_bytes());
+    bytes.extend_from_slice(&1u16.to_le_bytes());
+    bytes.extend_from_slice(&channels.to_le_bytes());
+    bytes.extend_from_slice(&sample_rate.to_le_bytes());
+    bytes.extend_from_slice(&(sample_rate * u32::from(channels) * 2).to_le_bytes());
+    bytes.extend_from_slice(&(channels * 2).to_le_bytes());
+    bytes.extend_from_slice(&16u16.to_le_bytes());
+    bytes.extend_from_slice(b"data");
+    bytes.extend_from_slice(&data_len.to_le_bytes());
+    for sample in samples {
+        bytes.extend_from_slice(&sample.to_le_bytes());
+    }
+
+    Source { bytes }
+}
+
+#[derive(Default)]
+pub struct Mixer {
+    decoded: HashMap<SourceHandle, Pcm>,
+}
+
+impl Mixer {
+    pub fn play(&mut self, handle: &SourceHandle, storage: &AssetStorage<Source>, output: &Output, pan: f32, pitch: f32, volume: f32) {
+        let source = match storage.get(handle) {
+            Some(source) => source,
+            None => return,
+        };
+
+        if !self.decoded.contains_key(handle) {
+            match Pcm::decode(source) {
+                Some(pcm) => { self.decoded.insert(handle.clone(), pcm); },
+                None => {
+                    output.play_once(source, volume);
+                    return;
+                },
+            }
+        }
+
+        output.play_once(&self.decoded[handle].render(pan, pitch), volume);
+    }
+}
@@ -0,0 +1,133 @@
+use std::{collections::HashMap, path::Path};
+use amethyst::{
+    assets::{Loader, AssetStorage},
+    audio::{Source, FlacFormat, OggFormat, WavFormat, SourceHandle},
+    ecs::{World, WorldExt},
+};
+use rand::{seq::SliceRandom, Rng};
+
+use crate::pong::{PongEvent, Side, AREA_WIDTH};
+
+mod backend;
+mod mixer;
+mod music;
+mod synth;
+mod table;
+
+pub use self::backend::{PlayLog, PlayRequest, SoundData, SoundOutput, ToneSink};
+pub use self::music::{Intensity, Music, PlaylistConfig, Track};
+pub use self::synth::{Blip, Waveform};
+pub use self::table::{SoundCue, SoundEntry, SoundTable};
+
+struct CueSounds {
+    entry: SoundEntry,
+    files: Vec<(String, SourceHandle)>,
+}
+
+pub struct Sounds {
+    prefer_synth: bool,
+    cues: HashMap<SoundCue, CueSounds>,
+}
+
+impl Sounds {
+    /// The table's cues without loading any files, so only synthesised entries can play.
+    #[cfg(test)]
+    pub fn synth_only(table: &SoundTable) -> Self {
+        let cues = table.cues
+            .iter()
+            .map(|(cue, entry)| (*cue, CueSounds { entry: entry.clone(), files: Vec::new() }))
+            .collect();
+        Self { prefer_synth: true, cues }
+    }
+}
+
+fn load_audio_track(loader: &Loader, world: &World, file: &str) -> SourceHandle {
+    let storage = world.read_resource::<AssetStorage<Source>>();
+    match Path::new(file).extension().and_then(|extension| extension.to_str()) {
+        Some("wav") => loader.load(file, WavFormat, (), &storage),
+        Some("flac") => loader.load(file, FlacFormat, (), &storage),
+        _ => loader.load(file, OggFormat, (), &storage),
+    }
+}
+
+pub fn initialise_audio(world: &mut World) {
+    let (sound, music) = {
+        let loader = world.read_resource::<Loader>();
+        let table = world.read_resource::<SoundTable>();
+
+        let playlist = world.read_resource::<PlaylistConfig>();
+
+        let load_tracks = |files: &[String]| -> Vec<Track> {
+            files
+                .iter()
+                .map(|file| Track::new(file, load_audio_track(&loader, &world, file)))
+                .collect()
+        };
+        let music = Music::new(load_tracks(&playlist.tracks), load_tracks(&playlist.intense_tracks), &playlist);
+
+        let cues = table.cues
+            .iter()
+            .map(|(cue, entry)| {
+                let files = entry.files
+                    .iter()
+                    .map(|file| (file.clone(), load_audio_track(&loader, &world, file)))
+                    .collect();
+                (*cue, CueSounds { entry: entry.clone(), files })
+            })
+            .collect();
+        let sound = Sounds { prefer_synth: table.prefer_synth, cues };
+
+        (sound, music)
+    };
+
+    world.insert(sound);
+    world.insert(music);
+}
+
+pub fn pan_for(x: f32) -> f32 {
+    (x / AREA_WIDTH * 2.0 - 1.0).max(-1.0).min(1.0)
+}
+
+fn cue_for_event(event: &PongEvent) -> (SoundCue, Option<[f32; 2]>, Option<f32>) {
+    match *event {
+        PongEvent::Bounce { side: Side::Left, position, speed, .. } => (SoundCue::LeftPaddleBounce, Some(position), Some(speed)),
+        PongEvent::Bounce { side: Side::Right, position, speed, .. } => (SoundCue::RightPaddleBounce, Some(position), Some(speed)),
+        PongEvent::WallBounce { position, speed } => (SoundCue::WallBounce, Some(position), Some(speed)),
+        PongEvent::BallCollision { position, speed, .. } => (SoundCue::BallCollision, Some(position), Some(speed)),
+        PongEvent::Serve { position } => (SoundCue::Serve, Some(position), None),
+        PongEvent::CountdownTick { position } => (SoundCue::CountdownTick, Some(position), None),
+        PongEvent::Score { position, speed, .. } => (SoundCue::Score, Some(position), Some(speed)),
+        PongEvent::MatchWon(_) => (SoundCue::MatchWon, None, None),
+    }
+}
+
+pub fn sound_request(sounds: &Sounds, storage: &AssetStorage<Source>, event: &PongEvent, volume: f32, reference_speed: f32, timestamp: f64) -> Option<PlayRequest> {
+    let (cue, position, speed) = cue_for_event(event);
+    let cue_sounds = sounds.cues.get(&cue)?;
+
+    let mut rng = rand::thread_rng();
+    let entry = &cue_sounds.entry;
+    let pan = position.map_or(0.0, |position| pan_for(position[0]));
+    let loudness = speed.map_or(1.0, |speed| (0.5 + 0.5 * speed / reference_speed).min(1.0));
+    let pitch = if entry.pitch_variation > 0.0 {
+        entry.pitch * (1.0 + rng.gen_range(-entry.pitch_variation, entry.pitch_variation))
+    } else {
+        entry.pitch
+    };
+
+    // Files that are missing or still loading are skipped, so the synth covers for them.
+    let loaded = cue_sounds.files
+        .iter()
+        .filter(|(_, handle)| storage.get(handle).is_some())
+        .collect::<Vec<_>>();
+
+    let (sound_id, data) = match (&entry.synth, loaded.choose(&mut rng).copied()) {
+        (Some(blip), file) if sounds.prefer_synth || file.is_none() => {
+            (format!("synth:{:?}", cue), SoundData::Synth(blip.clone(), speed.unwrap_or(0.0)))
+        },
+        (_, Some((file, handle))) => (file.clone(), SoundData::Sample(handle.clone())),
+        _ => return None,
+    };
+
+    Some(PlayRequest { cue: Some(cue), sound_id, data, volume: volume * entry.volume * loudness, pan, pitch, timestamp })
+}
@@ -0,0 +1,184 @@
+use std::path::Path;
+
+use amethyst::audio::SourceHandle;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+
+const MUSIC_DIR: &str = "audio/music";
+const INTENSE_MUSIC_DIR: &str = "audio/music/intense";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum RepeatMode {
+    Off, One, All,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct PlaylistConfig {
+    /// Files relative to the assets folder. Left empty, every `.ogg` in `audio/music/` is played.
+    pub tracks: Vec<String>,
+    /// Played at match point and during long rallies. Left empty, `audio/music/intense/` is searched.
+    pub intense_tracks: Vec<String>,
+    pub shuffle: bool,
+    pub repeat: RepeatMode,
+    pub crossfade_seconds: f32,
+    pub intense_rally_hits: u32,
+    pub duck_volume: f32,
+    pub duck_seconds: f32,
+}
+
+impl Default for PlaylistConfig {
+    fn default() -> Self {
+        Self {
+            tracks: Vec::new(),
+            intense_tracks: Vec::new(),
+            shuffle: false,
+            repeat: RepeatMode::All,
+            crossfade_seconds: 2.0,
+            intense_rally_hits: 12,
+            duck_volume: 0.4,
+            duck_seconds: 1.0,
+        }
+    }
+}
+
+impl PlaylistConfig {
+    pub fn with_discovered_tracks(mut self, asset_dir: &Path) -> Self {
+        if self.tracks.is_empty() {
+            self.tracks = discover_tracks(asset_dir, MUSIC_DIR);
+        }
+        if self.intense_tracks.is_empty() {
+            self.intense_tracks = discover_tracks(asset_dir, INTENSE_MUSIC_DIR);
+        }
+
+        self
+    }
+}
+
+fn discover_tracks(asset_dir: &Path, dir: &str) -> Vec<String> {
+    let entries = match std::fs::read_dir(asset_dir.join(dir)) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut tracks = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().map_or(false, |extension| extension == "ogg"))
+        .filter_map(|path| path.file_name().and_then(|name| name.to_str()).map(|name| format!("{}/{}", dir, name)))
+        .collect::<Vec<_>>();
+    tracks.sort();
+    tracks
+}
+
+pub struct Track {
+    pub name: String,
+    pub handle: SourceHandle,
+}
+
+impl Track {
+    pub fn new(file: &str, handle: SourceHandle) -> Self {
+        let name = Path::new(file)
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or(file)
+            .replace('_', " ");
+
+        Self { name, handle }
+    }
+}
+
+struct Playlist {
+    tracks: Vec<Track>,
+    order: Vec<usize>,
+    position: Option<usize>,
+    shuffle: bool,
+    repeat: RepeatMode,
+}
+
+impl Playlist {
+    fn new(tracks: Vec<Track>, config: &PlaylistConfig) -> Self {
+        let mut playlist = Self {
+            order: (0..tracks.len()).collect(),
+            tracks,
+            position: None,
+            shuffle: config.shuffle,
+            repeat: config.repeat,
+        };
+        playlist.reshuffle();
+        playlist
+    }
+
+    fn next(&mut self, skip: bool) -> Option<&Track> {
+        if self.tracks.is_empty() {
+            return None;
+        }
+
+        let repeat = if skip { RepeatMode::All } else { self.repeat };
+        let position = match (self.position, repeat) {
+            (None, _) => 0,
+            (Some(position), RepeatMode::One) => position,
+            (Some(position), _) if position + 1 < self.order.len() => position + 1,
+            (Some(_), RepeatMode::Off) => return None,
+            (Some(_), RepeatMode::All) => {
+                self.reshuffle();
+                0
+            },
+        };
+        self.position = Some(position);
+
+        Some(&self.tracks[self.order[position]])
+    }
+
+    fn reshuffle(&mut self) {
+        if self.shuffle {
+            self.order.shuffle(&mut rand::thread_rng());
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Intensity {
+    Calm, Intense,
+}
+
+pub struct Music {
+    calm: Playlist,
+    intense: Playlist,
+    skip_requested: bool,
+    pub intensity: Intensity,
+    pub announcement: Option<String>,
+}
+
+impl Music {
+    pub fn new(calm: Vec<Track>, intense: Vec<Track>, config: &PlaylistConfig) -> Self {
+        Self {
+            calm: Playlist::new(calm, config),
+            intense: Playlist::new(intense, config),
+            skip_requested: false,
+            intensity: Intensity::Calm,
+            announcement: None,
+        }
+    }
+
+    pub fn has_intense_tracks(&self) -> bool {
+        !self.intense.tracks.is_empty()
+    }
+
+    pub fn skip(&mut self) {
+        self.skip_requested = true;
+    }
+
+    pub fn next(&mut self) -> Option<SourceHandle> {
+        let skip = std::mem::replace(&mut self.skip_requested, false);
+        let playlist = if self.intensity == Intensity::Intense && self.has_intense_tracks() {
+            &mut self.intense
+        } else {
+            &mut self.calm
+        };
+
+        let track = playlist.next(skip)?;
+        self.announcement = Some(track.name.clone());
+        Some(track.handle.clone())
+    }
+}
@@ -0,0 +1,108 @@
+use std::f32::consts::PI;
+
+use serde::{Deserialize, Serialize};
+
+pub const SAMPLE_RATE: u32 = 44_100;
+
+const ATTACK: f32 = 0.05;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Waveform {
+    Square, Sine,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Blip {
+    pub waveform: Waveform,
+    pub frequency: f32,
+    pub duration: f32,
+    /// Hertz added per unit of ball speed, so faster balls sound higher.
+    pub speed_pitch: f32,
+    /// Frequency multiplier reached by the end of the blip.
+    pub sweep: f32,
+}
+
+impl Default for Blip {
+    fn default() -> Self {
+        Self { waveform: Waveform::Square, frequency: 440.0, duration: 0.05, speed_pitch: 0.0, sweep: 1.0 }
+    }
+}
+
+impl Blip {
+    pub fn samples(&self, speed: f32) -> Vec<f32> {
+        render(self.waveform, self.frequency + self.speed_pitch * speed, self.duration, self.sweep, SAMPLE_RATE)
+    }
+}
+
+/// Renders a mono tone as samples in `-1.0..=1.0`, with a short fade in and a linear fade out.
+pub fn render(waveform: Waveform, frequency: f32, duration: f32, sweep: f32, sample_rate: u32) -> Vec<f32> {
+    let count = (duration * sample_rate as f32).max(0.0) as usize;
+    let mut phase = 0.0f32;
+
+    (0..count)
+        .map(|index| {
+            let progress = index as f32 / count as f32;
+            let value = wave(waveform, phase);
+
+            phase = (phase + frequency * (1.0 + (sweep - 1.0) * progress) / sample_rate as f32).fract();
+            value * (progress / ATTACK).min(1.0) * (1.0 - progress)
+        })
+        .collect()
+}
+
+/// Renders whole cycles of a steady tone lasting at least `duration`, so segments can be queued back to back.
+pub fn render_cycles(waveform: Waveform, frequency: f32, duration: f32, sample_rate: u32) -> Vec<f32> {
+    let cycles = (frequency * duration).ceil().max(1.0);
+    let count = (cycles / frequency * sample_rate as f32).round() as usize;
+
+    (0..count)
+        .map(|index| wave(waveform, (index as f32 * frequency / sample_rate as f32).fract()))
+        .collect()
+}
+
+fn wave(waveform: Waveform, phase: f32) -> f32 {
+    match waveform {
+        Waveform::Square => if phase < 0.5 { 1.0 } else { -1.0 },
+        Waveform::Sine => (phase * 2.0 * PI).sin(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn zero_crossings(samples: &[f32]) -> usize {
+        samples.windows(2).filter(|pair| (pair[0] < 0.0) != (pair[1] < 0.0)).count()
+    }
+
+    #[test]
+    fn buffer_lasts_the_requested_duration() {
+        for duration in [0.25, 0.5, 1.0].iter() {
+            let samples = render(Waveform::Sine, 440.0, *duration, 1.0, SAMPLE_RATE);
+            assert_eq!(samples.len(), (*duration * SAMPLE_RATE as f32) as usize);
+        }
+    }
+
+    #[test]
+    fn samples_stay_in_range() {
+        for waveform in [Waveform::Square, Waveform::Sine].iter() {
+            let blip = Blip { waveform: *waveform, duration: 0.2, sweep: 2.0, ..Blip::default() };
+            assert!(blip.samples(0.0).iter().all(|sample| sample.abs() <= 1.0));
+            assert!(render_cycles(*waveform, 330.0, 0.1, SAMPLE_RATE).iter().all(|sample| sample.abs() <= 1.0));
+        }
+    }
+
+    #[test]
+    fn envelope_starts_and_ends_silent() {
+        let samples = Blip { duration: 0.2, ..Blip::default() }.samples(0.0);
+        assert!(samples[0].abs() < 0.01);
+        assert!(samples[samples.len() - 1].abs() < 0.01);
+    }
+
+    #[test]
+    fn faster_balls_raise_the_pitch() {
+        let blip = Blip { waveform: Waveform::Sine, duration: 0.2, speed_pitch: 2.0, ..Blip::default() };
+        assert!(zero_crossings(&blip.samples(200.0)) > zero_crossings(&blip.samples(0.0)));
+    }
+}
@@ -0,0 +1,43 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::synth::Blip;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum SoundCue {
+    WallBounce,
+    LeftPaddleBounce,
+    RightPaddleBounce,
+    BallCollision,
+    Serve,
+    CountdownTick,
+    Score,
+    MatchWon,
+    PowerUp,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SoundEntry {
+    pub files: Vec<String>,
+    pub volume: f32,
+    pub pitch: f32,
+    /// Each play shifts the pitch by a random amount up to this fraction either way.
+    pub pitch_variation: f32,
+    /// Generated in place of the files when the table prefers synthesis or lists no files.
+    pub synth: Option<Blip>,
+}
+
+impl Default for SoundEntry {
+    fn default() -> Self {
+        Self { files: Vec::new(), volume: 1.0, pitch: 1.0, pitch_variation: 0.0, synth: None }
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SoundTable {
+    pub prefer_synth: bool,
+    pub cues: HashMap<SoundCue, SoundEntry>,
+}
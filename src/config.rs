@@ -0,0 +1,258 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct GameConfig {
+    pub paddle: PaddleConfig,
+    pub spin: SpinConfig,
+    pub serve: ServeConfig,
+    pub mode: String,
+    pub modes: BTreeMap<String, ModeConfig>,
+    /// Rules used when game.ron defines no modes at all.
+    #[serde(skip)]
+    fallback_mode: ModeConfig,
+}
+
+impl GameConfig {
+    pub fn mode(&self) -> &ModeConfig {
+        self.modes.get(&self.mode).unwrap_or(&self.fallback_mode)
+    }
+
+    /// Points `mode` at a defined mode if it names one game.ron does not have.
+    pub fn check_mode(&mut self) {
+        if self.modes.contains_key(&self.mode) {
+            return;
+        }
+        match self.modes.keys().next().cloned() {
+            Some(first) => {
+                log::warn!("Game mode {} is not defined in game.ron, playing {} instead", self.mode, first);
+                self.mode = first;
+            },
+            None => log::warn!("game.ron defines no game modes, playing the built-in rules"),
+        }
+    }
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        let mut modes = BTreeMap::new();
+        modes.insert("classic".to_string(), ModeConfig::default());
+
+        Self {
+            paddle: PaddleConfig::default(),
+            spin: SpinConfig::default(),
+            serve: ServeConfig::default(),
+            mode: "classic".to_string(),
+            modes,
+            fallback_mode: ModeConfig::default(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct PaddleConfig {
+    pub max_speed: f32,
+    pub acceleration: f32,
+    pub friction: f32,
+    /// Share of the paddle's vertical velocity handed to the ball on contact.
+    pub english: f32,
+    /// How fast a paddle grows or shrinks towards a new size, in units per second.
+    pub resize_speed: f32,
+    pub dash: DashConfig,
+}
+
+impl Default for PaddleConfig {
+    fn default() -> Self {
+        Self { max_speed: 150.0, acceleration: 900.0, friction: 1200.0, english: 0.3, resize_speed: 30.0, dash: DashConfig::default() }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct DashConfig {
+    /// Paddle speed during a dash, as a multiple of its max speed.
+    pub speed_multiplier: f32,
+    /// Seconds a dash lasts.
+    pub duration: f32,
+    /// Seconds before the same paddle can dash again, counted from the start of a dash.
+    pub cooldown: f32,
+}
+
+impl Default for DashConfig {
+    fn default() -> Self {
+        Self { speed_multiplier: 2.5, duration: 0.15, cooldown: 2.0 }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SpinConfig {
+    /// Spin in radians per second given to the ball per unit of paddle velocity.
+    pub paddle_transfer: f32,
+    /// How hard spin bends the flight path, in radians of turn per radian of spin.
+    pub magnus: f32,
+    pub decay: f32,
+    pub max_spin: f32,
+    /// Sideways push per radian per second of spin when grazing a wall; it turns the ball without speeding it up.
+    pub wall_kick: f32,
+    pub wall_retention: f32,
+}
+
+impl Default for SpinConfig {
+    fn default() -> Self {
+        Self { paddle_transfer: 0.05, magnus: 0.05, decay: 0.5, max_spin: 12.0, wall_kick: 3.0, wall_retention: 0.5 }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub enum ServeStyle {
+    Automatic,
+    /// The ball waits on the server's paddle until they press their serve action.
+    Player,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ServeConfig {
+    pub style: ServeStyle,
+    /// Seconds a held ball waits before it is launched anyway.
+    pub timeout: f32,
+    /// Launch angle in degrees when the paddle is moving at full speed.
+    pub max_angle: f32,
+    /// Number of steps counted down before each serve.
+    pub countdown: u32,
+    /// Seconds each countdown number stays on screen.
+    pub countdown_step: f32,
+}
+
+impl ServeConfig {
+    pub fn countdown_seconds(&self) -> f32 {
+        self.countdown as f32 * self.countdown_step
+    }
+}
+
+impl Default for ServeConfig {
+    fn default() -> Self {
+        Self { style: ServeStyle::Automatic, timeout: 5.0, max_angle: 45.0, countdown: 3, countdown_step: 0.7 }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ModeConfig {
+    pub ball_speed: BallSpeedConfig,
+    pub points_to_win: u32,
+    pub shrink: Option<ShrinkRule>,
+    pub air_hockey: Option<AirHockeyConfig>,
+    /// Openings in the end walls; without them the whole wall is a goal.
+    pub goals: Option<GoalsConfig>,
+}
+
+impl Default for ModeConfig {
+    fn default() -> Self {
+        Self { ball_speed: BallSpeedConfig::default(), points_to_win: 11, shrink: None, air_hockey: None, goals: None }
+    }
+}
+
+/// Paddles roam their own half and the puck slides to a stop.
+/// The mode's speed curve becomes a speed limit, so friction and hits decide how fast the puck goes.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AirHockeyConfig {
+    /// Share of the puck's speed lost per second.
+    pub puck_friction: f32,
+}
+
+/// Goal openings keyed by the side that defends them.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct GoalsConfig {
+    pub left: GoalMouthConfig,
+    pub right: GoalMouthConfig,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct GoalMouthConfig {
+    pub height: f32,
+    /// Centre of the opening, measured up the end wall.
+    pub center: f32,
+    pub motion: Option<GoalMotion>,
+}
+
+/// Sways the opening up and down around its centre.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct GoalMotion {
+    pub range: f32,
+    /// Seconds for one full sweep up and back.
+    pub period: f32,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub enum ShrinkTrigger {
+    /// The scorer's paddle shrinks each time they win a point.
+    OwnScore,
+    /// Both paddles shrink every so many paddle hits over the whole match.
+    MatchHits(u32),
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ShrinkRule {
+    pub trigger: ShrinkTrigger,
+    pub step: f32,
+    pub min_height: f32,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct BallSpeedConfig {
+    pub base: f32,
+    pub max: f32,
+    pub curve: SpeedCurve,
+    pub rally_slowdown: Option<RallySlowdown>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub enum SpeedCurve {
+    Linear { per_hit: f32 },
+    Exponential { factor: f32 },
+    Stepped { every: u32, step: f32 },
+}
+
+/// Once a rally passes `after_hits`, every further hit scales the speed by `factor`.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct RallySlowdown {
+    pub after_hits: u32,
+    pub factor: f32,
+}
+
+impl BallSpeedConfig {
+    /// Ball speed after `hits` paddle hits, scaled by the difficulty `multiplier` and then held to `max`.
+    pub fn speed_after(&self, hits: u32, multiplier: f32) -> f32 {
+        let curve_hits = match &self.rally_slowdown {
+            Some(slowdown) => hits.min(slowdown.after_hits),
+            None => hits,
+        };
+
+        let speed = match self.curve {
+            SpeedCurve::Linear { per_hit } => self.base + per_hit * curve_hits as f32,
+            SpeedCurve::Exponential { factor } => self.base * factor.powi(curve_hits as i32),
+            SpeedCurve::Stepped { every, step } => self.base + step * (curve_hits / every.max(1)) as f32,
+        };
+
+        let speed = match &self.rally_slowdown {
+            Some(slowdown) if hits > slowdown.after_hits => {
+                speed * slowdown.factor.powi((hits - slowdown.after_hits) as i32)
+            },
+            _ => speed,
+        };
+
+        (speed.max(self.base) * multiplier).min(self.max)
+    }
+}
+
+impl Default for BallSpeedConfig {
+    fn default() -> Self {
+        Self { base: 90.0, max: 220.0, curve: SpeedCurve::Linear { per_hit: 6.0 }, rally_slowdown: None }
+    }
+}
@@ -10,17 +10,22 @@ use amethyst::{
     utils::application_root_dir,
     core::transform::TransformBundle,
     core::frame_limiter::FrameRateLimitStrategy,
-    input::{InputBundle, StringBindings},
+    input::{Bindings, InputBundle, StringBindings},
     ui::{RenderUi, UiBundle},
-    audio::{AudioBundle, DjSystemDesc},
+    audio::AudioBundle,
 };
 
 mod pong;
 mod audio;
+mod config;
+mod menu;
+mod settings;
 mod systems;
 
-use crate::pong::Pong;
-use crate::audio::Music;
+use crate::menu::MainMenu;
+use crate::audio::{PlaylistConfig, SoundTable};
+use crate::config::GameConfig;
+use crate::settings::{DefaultBindings, Settings, UserFiles};
 
 fn main() -> amethyst::Result<()> {
     amethyst::start_logger(Default::default());
@@ -28,9 +33,27 @@ fn main() -> amethyst::Result<()> {
     let display_config_path = app_root.join("config").join("display.ron");
     let asset_dir = app_root.join("assets");
     let binding_path = app_root.join("config").join("bindings.ron");
+    let mut game_config = GameConfig::load(app_root.join("config").join("game.ron"))?;
+    let sound_table = SoundTable::load(app_root.join("config").join("sounds.ron"))?;
+    let playlist = PlaylistConfig::load(app_root.join("config").join("music.ron"))?
+        .with_discovered_tracks(&asset_dir);
+    let user_files = UserFiles {
+        settings: app_root.join("user").join("settings.ron"),
+        bindings: app_root.join("user").join("bindings.ron"),
+    };
+    let settings = Settings::load(&user_files.settings).unwrap_or_default();
+    if let Some(mode) = settings.game.mode.as_ref().filter(|mode| game_config.modes.contains_key(*mode)) {
+        game_config.mode = mode.clone();
+    }
+    game_config.check_mode();
 
+    let default_bindings = DefaultBindings(Bindings::<StringBindings>::load(binding_path)?);
+    let bindings = match Bindings::<StringBindings>::load(&user_files.bindings) {
+        Ok(saved) => default_bindings.with_overrides(&saved),
+        Err(_) => default_bindings.0.clone(),
+    };
     let input_bundle = InputBundle::<StringBindings>::new()
-        .with_bindings_from_file(binding_path)?;
+        .with_bindings(bindings);
 
     let game_data = GameDataBuilder::default()
         .with_bundle(
@@ -46,17 +69,19 @@ fn main() -> amethyst::Result<()> {
         .with_bundle(input_bundle)?
         .with_bundle(UiBundle::<StringBindings>::new())?
         .with_bundle(AudioBundle::default())?
-        .with_system_desc(DjSystemDesc::new(|music: &mut Music| music.music.next()), "dj_system", &[])
         .with_system_desc(systems::AudioSystemDesc::default(), "custom_audio_system", &[])
-        .with(systems::InputSystem, "custom_input_system", &["input_system"])
-        .with(systems::PaddleSystem, "paddle_system", &["input_system"])
-        .with(systems::MoveBallsSystem, "move_ball_system", &[])
-        .with(systems::BounceSystem::new(), "bounce_system", &["paddle_system", "move_ball_system"])
-        .with(systems::WinnerSystem, "winner_system", &["move_ball_system"])
-        .with_system_desc(systems::VelocitySystemDesc::default(), "velocity_system", &["bounce_system", "move_ball_system"])
-        .with_system_desc(systems::BallSystemDesc::default(), "ball_system", &["winner_system"]);
+        .with_system_desc(systems::VolumeSystemDesc::default(), "volume_system", &["input_system"])
+        .with_system_desc(systems::MusicSystemDesc::default(), "music_system", &["input_system"])
+        .with_system_desc(systems::AudioGuideSystemDesc::default(), "audio_guide_system", &[])
+        .with(systems::InputSystem, "custom_input_system", &["input_system"]);
 
-    let mut game = Application::build(asset_dir, Pong::default())?
+    let mut game = Application::build(asset_dir, MainMenu::default())?
+        .with_resource(game_config)
+        .with_resource(sound_table)
+        .with_resource(playlist)
+        .with_resource(settings)
+        .with_resource(user_files)
+        .with_resource(default_bindings)
         .with_frame_limit(FrameRateLimitStrategy::SleepAndYield(Duration::from_millis(2)), 144)
         .build(game_data)?;
 
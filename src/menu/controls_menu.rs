@@ -0,0 +1,264 @@
+use amethyst::{
+    ecs::{World, WorldExt},
+    input::{Axis, Button, InputHandler, StringBindings},
+    prelude::*,
+    winit::{ElementState, Event, KeyboardInput, VirtualKeyCode, WindowEvent},
+};
+
+use crate::settings::{save_user_file, DefaultBindings, UserFiles};
+use super::{navigation, MenuScreen, Navigation};
+
+#[derive(Clone, Debug, PartialEq)]
+enum Row {
+    AxisPositive(String),
+    AxisNegative(String),
+    Action(String),
+    Reset,
+    Back,
+}
+
+impl Row {
+    fn label(&self) -> String {
+        match self {
+            Row::AxisPositive(axis) => format!("{} +", axis),
+            Row::AxisNegative(axis) => format!("{} -", axis),
+            Row::Action(action) => action.clone(),
+            Row::Reset => "Reset to defaults".to_string(),
+            Row::Back => "Back".to_string(),
+        }
+    }
+}
+
+pub struct ControlsMenu {
+    screen: MenuScreen,
+    rows: Vec<Row>,
+    selected: usize,
+    capturing: bool,
+    /// Keys held down so far while capturing; the binding is taken when the first of them is released.
+    combo: Vec<VirtualKeyCode>,
+    status: String,
+}
+
+impl Default for ControlsMenu {
+    fn default() -> Self {
+        Self {
+            screen: MenuScreen::new("ui/controls.ron", &[]),
+            rows: Vec::new(),
+            selected: 0,
+            capturing: false,
+            combo: Vec::new(),
+            status: String::new(),
+        }
+    }
+}
+
+impl SimpleState for ControlsMenu {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        self.rows = binding_rows(&data.world.read_resource::<InputHandler<StringBindings>>());
+        self.screen.show(data.world);
+    }
+
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        self.screen.hide(data.world);
+    }
+
+    fn handle_event(&mut self, data: StateData<'_, GameData<'_, '_>>, event: StateEvent) -> SimpleTrans {
+        if self.capturing {
+            match key_input(&event) {
+                Some((VirtualKeyCode::Escape, ElementState::Pressed)) if self.combo.is_empty() => {
+                    self.capturing = false;
+                    self.status = String::new();
+                },
+                Some((key, ElementState::Pressed)) if !self.combo.contains(&key) => self.combo.push(key),
+                Some((key, ElementState::Released)) if self.combo.contains(&key) => {
+                    self.capturing = false;
+                    let combo = self.combo.drain(..).map(Button::Key).collect::<Vec<_>>();
+                    self.status = rebind(data.world, &self.rows[self.selected], combo);
+                },
+                _ => {},
+            }
+            return Trans::None;
+        }
+
+        match navigation(&event) {
+            Some(Navigation::Up) => self.selected = (self.selected + self.rows.len() - 1) % self.rows.len(),
+            Some(Navigation::Down) => self.selected = (self.selected + 1) % self.rows.len(),
+            Some(Navigation::Select) => match self.rows[self.selected].clone() {
+                Row::Back => return Trans::Pop,
+                Row::Reset => {
+                    self.status = reset_bindings(data.world);
+                    self.rows = binding_rows(&data.world.read_resource::<InputHandler<StringBindings>>());
+                    self.selected = self.selected.min(self.rows.len() - 1);
+                },
+                _ => {
+                    self.capturing = true;
+                    self.status = "Press a key or combination, or Escape to cancel".to_string();
+                },
+            },
+            Some(Navigation::Back) => return Trans::Pop,
+            _ => {},
+        }
+        Trans::None
+    }
+
+    fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        let listing = {
+            let input = data.world.read_resource::<InputHandler<StringBindings>>();
+            self.rows
+                .iter()
+                .enumerate()
+                .map(|(index, row)| {
+                    let marker = if index == self.selected { "> " } else { "  " };
+                    let keys = if index == self.selected && self.capturing {
+                        "...".to_string()
+                    } else {
+                        describe_row(&input, row)
+                    };
+                    if keys.is_empty() {
+                        format!("{}{}", marker, row.label())
+                    } else {
+                        format!("{}{}: {}", marker, row.label(), keys)
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+
+        self.screen.set_text(data.world, "bindings", listing);
+        self.screen.set_text(data.world, "status", self.status.clone());
+        Trans::None
+    }
+}
+
+fn key_input(event: &StateEvent) -> Option<(VirtualKeyCode, ElementState)> {
+    match event {
+        StateEvent::Window(Event::WindowEvent {
+            event: WindowEvent::KeyboardInput {
+                input: KeyboardInput { state, virtual_keycode: Some(key), .. },
+                ..
+            },
+            ..
+        }) => Some((*key, *state)),
+        _ => None,
+    }
+}
+
+fn binding_rows(input: &InputHandler<StringBindings>) -> Vec<Row> {
+    let mut axes = input.bindings.axes().cloned().collect::<Vec<_>>();
+    axes.sort();
+    let mut actions = input.bindings.actions().cloned().collect::<Vec<_>>();
+    actions.sort();
+
+    axes.into_iter()
+        .flat_map(|axis| vec![Row::AxisPositive(axis.clone()), Row::AxisNegative(axis)])
+        .chain(actions.into_iter().map(Row::Action))
+        .chain(vec![Row::Reset, Row::Back])
+        .collect()
+}
+
+fn describe_row(input: &InputHandler<StringBindings>, row: &Row) -> String {
+    match (row, row_axis(input, row)) {
+        (Row::AxisPositive(_), Some((pos, _))) => describe_button(&pos),
+        (Row::AxisNegative(_), Some((_, neg))) => describe_button(&neg),
+        (Row::Action(action), _) => input.bindings.action_bindings(action)
+            .map(describe_combo)
+            .collect::<Vec<_>>()
+            .join(", "),
+        _ => String::new(),
+    }
+}
+
+fn row_axis(input: &InputHandler<StringBindings>, row: &Row) -> Option<(Button, Button)> {
+    let axis = match row {
+        Row::AxisPositive(axis) | Row::AxisNegative(axis) => axis,
+        _ => return None,
+    };
+    match input.bindings.axis(axis) {
+        Some(Axis::Emulated { pos, neg }) => Some((*pos, *neg)),
+        _ => None,
+    }
+}
+
+fn describe_button(button: &Button) -> String {
+    match button {
+        Button::Key(key) => format!("{:?}", key),
+        other => format!("{:?}", other),
+    }
+}
+
+fn describe_combo(combo: &[Button]) -> String {
+    combo.iter().map(describe_button).collect::<Vec<_>>().join("+")
+}
+
+/// Finds the row other than `except` that already uses `combo`. A single key clashes with any
+/// binding containing it; a combination only clashes with the same combination.
+fn bound_to(input: &InputHandler<StringBindings>, rows: &[Row], combo: &[Button], except: &Row) -> Option<Row> {
+    let single = match combo {
+        [button] => Some(*button),
+        _ => None,
+    };
+    rows.iter()
+        .filter(|row| *row != except)
+        .find(|row| match (*row, row_axis(input, row)) {
+            (Row::AxisPositive(_), Some((pos, _))) => single == Some(pos),
+            (Row::AxisNegative(_), Some((_, neg))) => single == Some(neg),
+            (Row::Action(action), _) => input.bindings.action_bindings(action).any(|bound| match single {
+                Some(button) => bound.contains(&button),
+                None => bound == combo,
+            }),
+            _ => false,
+        })
+        .cloned()
+}
+
+fn rebind(world: &mut World, row: &Row, combo: Vec<Button>) -> String {
+    let result = {
+        let mut input = world.write_resource::<InputHandler<StringBindings>>();
+        let rows = binding_rows(&input);
+        if let Some(other) = bound_to(&input, &rows, &combo, row) {
+            return format!("{} is already bound to {}", describe_combo(&combo), other.label());
+        }
+
+        match (row, row_axis(&input, row), combo.as_slice()) {
+            (Row::AxisPositive(_), _, [_, _, ..]) | (Row::AxisNegative(_), _, [_, _, ..]) => {
+                return "Axes take a single key".to_string();
+            },
+            (Row::AxisPositive(axis), Some((_, neg)), [button]) => {
+                input.bindings.insert_axis(axis.clone(), Axis::Emulated { pos: *button, neg }).map(|_| ())
+            },
+            (Row::AxisNegative(axis), Some((pos, _)), [button]) => {
+                input.bindings.insert_axis(axis.clone(), Axis::Emulated { pos, neg: *button }).map(|_| ())
+            },
+            (Row::Action(action), _, _) => {
+                input.bindings.remove_action(action);
+                input.bindings.insert_action_binding(action.clone(), combo.iter().cloned())
+            },
+            _ => return String::new(),
+        }
+    };
+
+    match result {
+        Ok(()) => save_bindings(world),
+        Err(error) => format!("Could not bind {}: {}", describe_combo(&combo), error),
+    }
+}
+
+fn save_bindings(world: &World) -> String {
+    let input = world.read_resource::<InputHandler<StringBindings>>();
+    let user_files = world.read_resource::<UserFiles>();
+    match save_user_file(&input.bindings, &user_files.bindings) {
+        Ok(()) => "Saved".to_string(),
+        Err(error) => format!("Could not save bindings: {}", error),
+    }
+}
+
+fn reset_bindings(world: &mut World) -> String {
+    let defaults = world.read_resource::<DefaultBindings>().0.clone();
+    world.write_resource::<InputHandler<StringBindings>>().bindings = defaults;
+
+    let user_files = world.read_resource::<UserFiles>();
+    match std::fs::remove_file(&user_files.bindings) {
+        Err(error) if error.kind() != std::io::ErrorKind::NotFound => format!("Could not remove saved bindings: {}", error),
+        _ => "Restored default bindings".to_string(),
+    }
+}
@@ -0,0 +1,37 @@
+use amethyst::prelude::*;
+
+use super::{MenuInput, MenuScreen};
+
+const ITEMS: &[&str] = &["back"];
+
+pub struct CreditsMenu {
+    screen: MenuScreen,
+}
+
+impl Default for CreditsMenu {
+    fn default() -> Self {
+        Self { screen: MenuScreen::new("ui/credits.ron", ITEMS) }
+    }
+}
+
+impl SimpleState for CreditsMenu {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        self.screen.show(data.world);
+    }
+
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        self.screen.hide(data.world);
+    }
+
+    fn handle_event(&mut self, _data: StateData<'_, GameData<'_, '_>>, event: StateEvent) -> SimpleTrans {
+        match self.screen.handle_event(&event) {
+            Some(MenuInput::Select(_)) | Some(MenuInput::Back) => Trans::Pop,
+            _ => Trans::None,
+        }
+    }
+
+    fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        self.screen.update(data.world);
+        Trans::None
+    }
+}
@@ -0,0 +1,103 @@
+use amethyst::{
+    ecs::{World, WorldExt},
+    prelude::*,
+};
+
+use crate::config::GameConfig;
+use crate::pong::{MatchRules, Side};
+use crate::settings::Settings;
+use super::{save_settings, MenuInput, MenuScreen};
+
+const ITEMS: &[&str] = &["left_size", "left_speed", "left_bonus", "right_size", "right_speed", "right_bonus", "back"];
+const MULTIPLIER_STEP: f32 = 0.1;
+const MIN_MULTIPLIER: f32 = 0.5;
+const MAX_MULTIPLIER: f32 = 2.0;
+const MAX_BONUS_POINTS: u32 = 10;
+
+pub struct HandicapMenu {
+    screen: MenuScreen,
+}
+
+impl Default for HandicapMenu {
+    fn default() -> Self {
+        Self { screen: MenuScreen::new("ui/handicaps.ron", ITEMS) }
+    }
+}
+
+impl SimpleState for HandicapMenu {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        self.screen.show(data.world);
+    }
+
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        self.screen.hide(data.world);
+    }
+
+    fn handle_event(&mut self, data: StateData<'_, GameData<'_, '_>>, event: StateEvent) -> SimpleTrans {
+        match self.screen.handle_event(&event) {
+            Some(MenuInput::Select("back")) | Some(MenuInput::Back) => Trans::Pop,
+            Some(MenuInput::Select(item)) => { adjust(data.world, item, 1); Trans::None },
+            Some(MenuInput::Adjust(item, step)) => { adjust(data.world, item, step); Trans::None },
+            None => Trans::None,
+        }
+    }
+
+    fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        let world = &mut *data.world;
+        let labels = {
+            let settings = world.read_resource::<Settings>();
+            let max_bonus = max_bonus_points(world, &settings);
+            let mut labels = Vec::new();
+            for (prefix, side) in [("left", Side::Left), ("right", Side::Right)].iter() {
+                let handicap = settings.game.handicap(*side);
+                labels.push((format!("{}_size", prefix), format!("Paddle size: {:.0}%", handicap.paddle_size * 100.)));
+                labels.push((format!("{}_speed", prefix), format!("Paddle speed: {:.0}%", handicap.paddle_speed * 100.)));
+                labels.push((format!("{}_bonus", prefix), format!("Starting points: {}", handicap.bonus_points.min(max_bonus))));
+            }
+            labels
+        };
+
+        for (id, text) in labels {
+            self.screen.set_text(world, &id, text);
+        }
+        self.screen.update(world);
+        Trans::None
+    }
+}
+
+fn adjust(world: &mut World, item: &str, step: i32) {
+    let (side, field) = match item.find('_') {
+        Some(split) => (&item[..split], &item[split + 1..]),
+        None => return,
+    };
+    let side = if side == "left" { Side::Left } else { Side::Right };
+
+    {
+        let mut settings = world.write_resource::<Settings>();
+        let max_bonus = max_bonus_points(world, &settings) as i32;
+        let handicap = settings.game.handicap_mut(side);
+        let multiplier_step = MULTIPLIER_STEP * step as f32;
+
+        match field {
+            "size" => handicap.paddle_size = step_multiplier(handicap.paddle_size, multiplier_step),
+            "speed" => handicap.paddle_speed = step_multiplier(handicap.paddle_speed, multiplier_step),
+            "bonus" => {
+                let current = (handicap.bonus_points as i32).min(max_bonus);
+                handicap.bonus_points = (current + step).max(0).min(max_bonus) as u32;
+            },
+            _ => return,
+        }
+    }
+
+    save_settings(world);
+}
+
+fn max_bonus_points(world: &World, settings: &Settings) -> u32 {
+    let points_to_win = MatchRules::points_to_win(&world.read_resource::<GameConfig>(), settings);
+    MatchRules::max_bonus_points(points_to_win).min(MAX_BONUS_POINTS)
+}
+
+fn step_multiplier(value: f32, step: f32) -> f32 {
+    // Rounded to a tenth so repeated steps land back on exactly 1.0.
+    ((value + step) * 10.).round().max(MIN_MULTIPLIER * 10.).min(MAX_MULTIPLIER * 10.) / 10.
+}
@@ -0,0 +1,78 @@
+use amethyst::{
+    ecs::{World, WorldExt},
+    prelude::*,
+};
+
+use crate::audio::initialise_audio;
+use crate::config::GameConfig;
+use crate::pong::{initialise_now_playing, load_font, Pong};
+use crate::settings::Settings;
+use super::{save_settings, CreditsMenu, HandicapMenu, MenuInput, MenuScreen, SettingsMenu};
+
+const ITEMS: &[&str] = &["play", "mode", "handicaps", "settings", "credits", "quit"];
+
+pub struct MainMenu {
+    screen: MenuScreen,
+}
+
+impl Default for MainMenu {
+    fn default() -> Self {
+        Self { screen: MenuScreen::new("ui/main_menu.ron", ITEMS) }
+    }
+}
+
+impl SimpleState for MainMenu {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+
+        let font = load_font(world);
+        initialise_now_playing(world, font);
+        initialise_audio(world);
+
+        self.screen.show(world);
+    }
+
+    fn on_pause(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        self.screen.hide(data.world);
+    }
+
+    fn on_resume(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        self.screen.show(data.world);
+    }
+
+    fn handle_event(&mut self, data: StateData<'_, GameData<'_, '_>>, event: StateEvent) -> SimpleTrans {
+        match self.screen.handle_event(&event) {
+            Some(MenuInput::Select("play")) => Trans::Push(Box::new(Pong::default())),
+            Some(MenuInput::Select("mode")) => { cycle_mode(data.world, 1); Trans::None },
+            Some(MenuInput::Adjust("mode", step)) => { cycle_mode(data.world, step); Trans::None },
+            Some(MenuInput::Select("handicaps")) => Trans::Push(Box::new(HandicapMenu::default())),
+            Some(MenuInput::Select("settings")) => Trans::Push(Box::new(SettingsMenu::default())),
+            Some(MenuInput::Select("credits")) => Trans::Push(Box::new(CreditsMenu::default())),
+            Some(MenuInput::Select("quit")) | Some(MenuInput::Back) => Trans::Quit,
+            _ => Trans::None,
+        }
+    }
+
+    fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        let mode = data.world.read_resource::<GameConfig>().mode.clone();
+        self.screen.set_text(data.world, "mode", format!("Mode: {}", mode));
+        self.screen.update(data.world);
+        Trans::None
+    }
+}
+
+fn cycle_mode(world: &mut World, step: i32) {
+    let mode = {
+        let mut config = world.write_resource::<GameConfig>();
+        let names = config.modes.keys().cloned().collect::<Vec<_>>();
+        if names.is_empty() {
+            return;
+        }
+        let current = names.iter().position(|name| *name == config.mode).unwrap_or(0) as i32;
+        config.mode = names[(current + step).rem_euclid(names.len() as i32) as usize].clone();
+        config.mode.clone()
+    };
+
+    world.write_resource::<Settings>().game.mode = Some(mode);
+    save_settings(world);
+}
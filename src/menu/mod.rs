@@ -0,0 +1,130 @@
+use amethyst::{
+    core::ParentHierarchy,
+    ecs::{Entity, World, WorldExt},
+    input::{is_key_down, VirtualKeyCode},
+    ui::{UiCreator, UiFinder, UiText},
+    StateEvent,
+};
+
+use crate::settings::{Settings, UserFiles};
+
+mod controls_menu;
+mod credits_menu;
+mod handicap_menu;
+mod main_menu;
+mod settings_menu;
+
+pub use self::controls_menu::ControlsMenu;
+pub use self::credits_menu::CreditsMenu;
+pub use self::handicap_menu::HandicapMenu;
+pub use self::main_menu::MainMenu;
+pub use self::settings_menu::SettingsMenu;
+
+const SELECTED_COLOR: [f32; 4] = [1.0, 0.85, 0.2, 1.0];
+const ITEM_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+
+pub enum Navigation {
+    Up, Down, Left, Right, Select, Back
+}
+
+pub fn navigation(event: &StateEvent) -> Option<Navigation> {
+    let event = match event {
+        StateEvent::Window(event) => event,
+        _ => return None,
+    };
+
+    if is_key_down(event, VirtualKeyCode::Up) || is_key_down(event, VirtualKeyCode::W) {
+        Some(Navigation::Up)
+    } else if is_key_down(event, VirtualKeyCode::Down) || is_key_down(event, VirtualKeyCode::S) {
+        Some(Navigation::Down)
+    } else if is_key_down(event, VirtualKeyCode::Left) || is_key_down(event, VirtualKeyCode::A) {
+        Some(Navigation::Left)
+    } else if is_key_down(event, VirtualKeyCode::Right) || is_key_down(event, VirtualKeyCode::D) {
+        Some(Navigation::Right)
+    } else if is_key_down(event, VirtualKeyCode::Return) || is_key_down(event, VirtualKeyCode::Space) {
+        Some(Navigation::Select)
+    } else if is_key_down(event, VirtualKeyCode::Escape) {
+        Some(Navigation::Back)
+    } else {
+        None
+    }
+}
+
+pub enum MenuInput {
+    Select(&'static str),
+    Adjust(&'static str, i32),
+    Back,
+}
+
+/// A screen loaded from a UI prefab whose `items` are the ids of its selectable labels, top to bottom.
+pub struct MenuScreen {
+    prefab: &'static str,
+    items: &'static [&'static str],
+    selected: usize,
+    root: Option<Entity>,
+}
+
+impl MenuScreen {
+    pub fn new(prefab: &'static str, items: &'static [&'static str]) -> Self {
+        Self { prefab, items, selected: 0, root: None }
+    }
+
+    pub fn show(&mut self, world: &mut World) {
+        let prefab = self.prefab;
+        self.root = Some(world.exec(|mut creator: UiCreator<'_>| creator.create(prefab, ())));
+    }
+
+    pub fn hide(&mut self, world: &mut World) {
+        if let Some(root) = self.root.take() {
+            let mut entities = world.read_resource::<ParentHierarchy>().all_children_iter(root).collect::<Vec<_>>();
+            entities.push(root);
+            if let Err(error) = world.delete_entities(&entities) {
+                log::warn!("Failed to remove menu {}: {}", self.prefab, error);
+            }
+        }
+    }
+
+    pub fn handle_event(&mut self, event: &StateEvent) -> Option<MenuInput> {
+        let count = self.items.len();
+        let selected = self.items[self.selected];
+
+        match navigation(event)? {
+            Navigation::Up => { self.selected = (self.selected + count - 1) % count; None },
+            Navigation::Down => { self.selected = (self.selected + 1) % count; None },
+            Navigation::Left => Some(MenuInput::Adjust(selected, -1)),
+            Navigation::Right => Some(MenuInput::Adjust(selected, 1)),
+            Navigation::Select => Some(MenuInput::Select(selected)),
+            Navigation::Back => Some(MenuInput::Back),
+        }
+    }
+
+    // Prefab widgets appear a few frames after `show`, so lookups are retried every update.
+    pub fn update(&self, world: &mut World) {
+        for (index, id) in self.items.iter().enumerate() {
+            if let Some(entity) = find(world, id) {
+                if let Some(text) = world.write_storage::<UiText>().get_mut(entity) {
+                    text.color = if index == self.selected { SELECTED_COLOR } else { ITEM_COLOR };
+                }
+            }
+        }
+    }
+
+    pub fn set_text(&self, world: &mut World, id: &str, value: String) {
+        if let Some(entity) = find(world, id) {
+            if let Some(text) = world.write_storage::<UiText>().get_mut(entity) {
+                text.text = value;
+            }
+        }
+    }
+}
+
+fn find(world: &mut World, id: &str) -> Option<Entity> {
+    world.exec(|finder: UiFinder<'_>| finder.find(id))
+}
+
+pub fn save_settings(world: &World) {
+    let user_files = world.read_resource::<UserFiles>();
+    if let Err(error) = world.read_resource::<Settings>().save(&user_files.settings) {
+        log::error!("Could not save settings: {}", error);
+    }
+}
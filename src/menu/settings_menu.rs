@@ -0,0 +1,105 @@
+use amethyst::{
+    ecs::{World, WorldExt},
+    prelude::*,
+};
+
+use crate::config::GameConfig;
+use crate::settings::Settings;
+use super::{save_settings, ControlsMenu, MenuInput, MenuScreen};
+
+const ITEMS: &[&str] = &["master", "music", "sfx", "difficulty", "match_length", "audio_guide", "left_device", "right_device", "controls", "back"];
+const VOLUME_STEP: f32 = 0.1;
+const MAX_POINTS: u32 = 99;
+
+pub struct SettingsMenu {
+    screen: MenuScreen,
+}
+
+impl Default for SettingsMenu {
+    fn default() -> Self {
+        Self { screen: MenuScreen::new("ui/settings.ron", ITEMS) }
+    }
+}
+
+impl SimpleState for SettingsMenu {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        self.screen.show(data.world);
+    }
+
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        self.screen.hide(data.world);
+    }
+
+    fn on_pause(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        self.screen.hide(data.world);
+    }
+
+    fn on_resume(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        self.screen.show(data.world);
+    }
+
+    fn handle_event(&mut self, data: StateData<'_, GameData<'_, '_>>, event: StateEvent) -> SimpleTrans {
+        match self.screen.handle_event(&event) {
+            Some(MenuInput::Select("controls")) => Trans::Push(Box::new(ControlsMenu::default())),
+            Some(MenuInput::Select("back")) | Some(MenuInput::Back) => Trans::Pop,
+            Some(MenuInput::Select(item)) => { adjust(data.world, item, 1); Trans::None },
+            Some(MenuInput::Adjust(item, step)) => { adjust(data.world, item, step); Trans::None },
+            None => Trans::None,
+        }
+    }
+
+    fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        let world = &mut *data.world;
+        let labels = {
+            let settings = world.read_resource::<Settings>();
+            let default_points = world.read_resource::<GameConfig>().mode().points_to_win;
+            let match_length = match settings.game.points_to_win {
+                Some(points) => format!("Match length: {} points", points),
+                None => format!("Match length: mode default ({})", default_points),
+            };
+
+            [
+                ("master", format!("Master volume: {:.0}%", settings.audio.master * 100.)),
+                ("music", format!("Music volume: {:.0}%", settings.audio.music * 100.)),
+                ("sfx", format!("Effects volume: {:.0}%", settings.audio.sfx * 100.)),
+                ("difficulty", format!("Difficulty: {:?}", settings.game.difficulty)),
+                ("match_length", match_length),
+                ("audio_guide", format!("Audio guide: {}", if settings.accessibility.audio_guide { "On" } else { "Off" })),
+                ("left_device", format!("Left paddle: {:?}", settings.controls.left)),
+                ("right_device", format!("Right paddle: {:?}", settings.controls.right)),
+            ]
+        };
+
+        for (id, text) in labels.iter() {
+            self.screen.set_text(world, id, text.clone());
+        }
+        self.screen.update(world);
+        Trans::None
+    }
+}
+
+fn adjust(world: &mut World, item: &str, step: i32) {
+    {
+        let default_points = world.read_resource::<GameConfig>().mode().points_to_win;
+        let mut settings = world.write_resource::<Settings>();
+        let Settings { audio, accessibility, game, controls } = &mut *settings;
+        let volume_step = VOLUME_STEP * step as f32;
+
+        match item {
+            "master" => audio.master = (audio.master + volume_step).max(0.0).min(1.0),
+            "music" => audio.music = (audio.music + volume_step).max(0.0).min(1.0),
+            "sfx" => audio.sfx = (audio.sfx + volume_step).max(0.0).min(1.0),
+            "difficulty" => game.difficulty = game.difficulty.cycle(step),
+            "match_length" => {
+                let points = game.points_to_win.unwrap_or(default_points) as i32 + step;
+                game.points_to_win = Some(points.max(1).min(MAX_POINTS as i32) as u32);
+            },
+            "audio_guide" => accessibility.audio_guide = !accessibility.audio_guide,
+            "left_device" => controls.left = controls.left.toggle(),
+            "right_device" => controls.right = controls.right.toggle(),
+            _ => return,
+        }
+    }
+
+    save_settings(world);
+}
@@ -1,19 +1,26 @@
 use amethyst::{
     assets::{AssetStorage, Loader, Handle},
-    core::{transform::Transform},
-    ecs::{World, Component, DenseVecStorage, Entity},
+    core::{math::Vector3, transform::Transform, Hidden},
+    ecs::{World, Component, DenseVecStorage, Dispatcher, Entity, Join, WriteStorage},
+    input::{is_key_down, VirtualKeyCode},
     prelude::*,
     renderer::{Camera, ImageFormat, SpriteRender, SpriteSheet, SpriteSheetFormat, Texture},
-    ui::{Anchor, UiText, LineMode, TtfFormat, UiTransform},
+    ui::{Anchor, FontHandle, UiText, LineMode, TtfFormat, UiTransform},
 };
 
-use crate::audio::initialise_audio;
+use serde::{Deserialize, Serialize};
+
+use crate::config::GameConfig;
+use crate::settings::{Handicap, Settings};
+use crate::systems;
 
 pub const AREA_HEIGHT: f32 = 100.0;
 pub const AREA_WIDTH: f32 = 100.0;
 
 pub const PADDLE_HEIGHT: f32 = 20.0;
 pub const PADDLE_WIDTH: f32 = 4.0;
+pub const PADDLE_SPRITE_HEIGHT: f32 = 16.0;
+pub const PADDLE_SPRITE_WIDTH: f32 = 4.0;
 
 pub const BALL_VELOCITY_X: f32 = 75.0;
 pub const BALL_VELOCITY_Y: f32 = 50.0;
@@ -21,13 +28,112 @@ pub const BALL_RADIUS:f32 = 2.0;
 
 #[derive(Debug)]
 pub enum PongEvent {
-    Bounce(&'static str), Score,
+    Bounce { ball: &'static str, side: Side, position: [f32; 2], speed: f32 },
+    WallBounce { position: [f32; 2], speed: f32 },
+    BallCollision { balls: [Entity; 2], position: [f32; 2], speed: f32 },
+    Serve { position: [f32; 2] },
+    CountdownTick { position: [f32; 2] },
+    Score { side: Side, position: [f32; 2], speed: f32 },
+    MatchWon(Side),
 }
 
 #[derive(Default)]
 pub struct Pong {
-    ball_spawn_timer: Option<f32>,
     sprite_sheet_handler: Option<Handle<SpriteSheet>>,
+    dispatcher: Option<Dispatcher<'static, 'static>>,
+}
+
+pub struct MatchRules {
+    pub points_to_win: u32,
+    pub speed_multiplier: f32,
+    pub starting_score: [f32; 2],
+}
+
+impl MatchRules {
+    pub fn new(config: &GameConfig, settings: &Settings) -> Self {
+        let points_to_win = Self::points_to_win(config, settings);
+        let bonus = |handicap: &Handicap| handicap.bonus_points.min(Self::max_bonus_points(points_to_win)) as f32;
+        Self {
+            points_to_win,
+            speed_multiplier: settings.game.difficulty.ball_speed_multiplier(),
+            starting_score: [bonus(&settings.game.left_handicap), bonus(&settings.game.right_handicap)],
+        }
+    }
+
+    pub fn points_to_win(config: &GameConfig, settings: &Settings) -> u32 {
+        settings.game.points_to_win.unwrap_or(config.mode().points_to_win)
+    }
+
+    /// Most starting points a handicap can give; everyone still has at least one point to play for.
+    pub fn max_bonus_points(points_to_win: u32) -> u32 {
+        points_to_win.saturating_sub(1)
+    }
+
+    pub fn starting_board(&self) -> ScoreBoard {
+        ScoreBoard { score_left: self.starting_score[0], score_right: self.starting_score[1] }
+    }
+}
+
+impl Default for MatchRules {
+    fn default() -> Self {
+        Self { points_to_win: 11, speed_multiplier: 1.0, starting_score: [0.0, 0.0] }
+    }
+}
+
+/// The opening in each end wall, indexed by the side defending it. The rest of the wall bounces the ball.
+pub struct GoalMouths {
+    pub mouths: [GoalMouth; 2],
+}
+
+#[derive(Clone, Copy)]
+pub struct GoalMouth {
+    pub center: f32,
+    pub height: f32,
+}
+
+impl GoalMouths {
+    pub fn new(config: &GameConfig) -> Self {
+        match &config.mode().goals {
+            Some(goals) => Self {
+                mouths: [
+                    GoalMouth { center: goals.left.center, height: goals.left.height },
+                    GoalMouth { center: goals.right.center, height: goals.right.height },
+                ],
+            },
+            None => Self::default(),
+        }
+    }
+
+    pub fn mouth(&self, side: Side) -> &GoalMouth {
+        match side {
+            Side::Left => &self.mouths[0],
+            Side::Right => &self.mouths[1],
+        }
+    }
+
+    pub fn contains(&self, side: Side, y: f32) -> bool {
+        let mouth = self.mouth(side);
+        (y - mouth.center).abs() <= mouth.height * 0.5
+    }
+}
+
+impl Default for GoalMouths {
+    fn default() -> Self {
+        let open = GoalMouth { center: AREA_HEIGHT * 0.5, height: AREA_HEIGHT };
+        Self { mouths: [open, open] }
+    }
+}
+
+/// Time left before the next ball is served, and where it will appear.
+#[derive(Default)]
+pub struct ServeCountdown {
+    pub remaining: Option<f32>,
+    pub position: [f32; 2],
+}
+
+pub struct CountdownDisplay {
+    pub text: Entity,
+    pub marker: Entity,
 }
 
 pub struct SpriteSheetHandler {
@@ -38,18 +144,86 @@ impl SimpleState for Pong {
     fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
         let world = data.world;
 
+        // Setting the systems up first registers every component the match entities use.
+        self.dispatcher = Some(systems::gameplay_dispatcher(world));
+
         let sprite_sheet_handler = load_sprite_sheet(world);
 
-        self.ball_spawn_timer.replace(1.0);
         self.sprite_sheet_handler.replace(sprite_sheet_handler.clone());
 
-        initialise_paddles(world, self.sprite_sheet_handler.clone().unwrap());
+        let font = load_font(world);
+
+        let rules = MatchRules::new(&world.read_resource::<GameConfig>(), &world.read_resource::<Settings>());
+        let handicaps = {
+            let settings = world.read_resource::<Settings>();
+            let mut handicaps = [settings.game.left_handicap, settings.game.right_handicap];
+            for handicap in handicaps.iter_mut() {
+                handicap.bonus_points = handicap.bonus_points.min(MatchRules::max_bonus_points(rules.points_to_win));
+            }
+            handicaps
+        };
+
+        initialise_paddles(world, self.sprite_sheet_handler.clone().unwrap(), handicaps);
         initialise_camera(world);
-        initialise_scoreboard(world);
-        initialise_audio(world);
+        initialise_scoreboard(world, font.clone(), &rules.starting_board(), handicaps);
+        initialise_countdown(world, font, self.sprite_sheet_handler.clone().unwrap());
+        initialise_goals(world, self.sprite_sheet_handler.clone().unwrap());
 
+        world.insert(rules.starting_board());
+        world.insert(rules);
         world.insert(SpriteSheetHandler { sprite_sheet_handler });
     }
+
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+        self.dispatcher = None;
+
+        let mut doomed = {
+            let entities = world.entities();
+            let paddles = world.read_storage::<Paddle>();
+            let balls = world.read_storage::<Ball>();
+            let cameras = world.read_storage::<Camera>();
+            let bars = world.read_storage::<CooldownBar>();
+            let markers = world.read_storage::<GoalMarker>();
+
+            (&entities, &paddles).join().map(|(entity, _)| entity)
+                .chain((&entities, &bars).join().map(|(entity, _)| entity))
+                .chain((&entities, &markers).join().map(|(entity, _)| entity))
+                .chain((&entities, &balls).join().map(|(entity, _)| entity))
+                .chain((&entities, &cameras).join().map(|(entity, _)| entity))
+                .collect::<Vec<_>>()
+        };
+        if let Some(text) = world.remove::<ScoreText>() {
+            doomed.push(text.p1_score);
+            doomed.push(text.p2_score);
+            doomed.extend(text.handicaps.iter());
+        }
+        if let Some(countdown) = world.remove::<CountdownDisplay>() {
+            doomed.push(countdown.text);
+            doomed.push(countdown.marker);
+        }
+        if let Err(error) = world.delete_entities(&doomed) {
+            log::warn!("Failed to clean up match entities: {}", error);
+        }
+
+        world.insert(ScoreBoard::default());
+    }
+
+    fn handle_event(&mut self, _data: StateData<'_, GameData<'_, '_>>, event: StateEvent) -> SimpleTrans {
+        if let StateEvent::Window(event) = &event {
+            if is_key_down(event, VirtualKeyCode::Escape) {
+                return Trans::Pop;
+            }
+        }
+        Trans::None
+    }
+
+    fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        if let Some(dispatcher) = self.dispatcher.as_mut() {
+            dispatcher.dispatch(&data.world);
+        }
+        Trans::None
+    }
 }
 
 fn initialise_camera(world: &mut World) {
@@ -63,19 +237,74 @@ fn initialise_camera(world: &mut World) {
         .build();
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
 pub enum Side {
     Left, Right
 }
 
+impl Side {
+    pub fn opposite(self) -> Side {
+        match self {
+            Side::Left => Side::Right,
+            Side::Right => Side::Left,
+        }
+    }
+}
+
 pub struct Paddle {
     pub side : Side,
     pub width : f32,
     pub height : f32,
+    pub velocity: [f32; 2],
+    pub input: [f32; 2],
+    /// Height the paddle is growing or shrinking towards; `height` follows it over a few frames.
+    pub target_height: f32,
+    /// Size at the start of a match, after handicaps.
+    pub base_height: f32,
+    pub speed_multiplier: f32,
 }
 
 impl Paddle {
-    fn new(side: Side) -> Paddle {
-        Paddle { side, width: PADDLE_WIDTH, height: PADDLE_HEIGHT }
+    fn new(side: Side, handicap: Handicap) -> Paddle {
+        let height = PADDLE_HEIGHT * handicap.paddle_size;
+        Paddle {
+            side,
+            width: PADDLE_WIDTH,
+            height,
+            velocity: [0.0, 0.0],
+            input: [0.0, 0.0],
+            target_height: height,
+            base_height: height,
+            speed_multiplier: handicap.paddle_speed,
+        }
+    }
+
+    pub fn x(&self) -> f32 {
+        match self.side {
+            Side::Left => self.width * 0.5,
+            Side::Right => AREA_WIDTH - self.width * 0.5,
+        }
+    }
+
+    pub fn serve_position(&self, paddle_position: [f32; 2], ball_radius: f32) -> [f32; 2] {
+        let reach = self.width * 0.5 + ball_radius + 1.0;
+        match self.side {
+            Side::Left => [paddle_position[0] + reach, paddle_position[1]],
+            Side::Right => [paddle_position[0] - reach, paddle_position[1]],
+        }
+    }
+
+    /// Horizontal extent the paddle's centre may move within; free paddles keep to their own half.
+    pub fn x_range(&self, free: bool) -> [f32; 2] {
+        match (free, self.side) {
+            (false, _) => [self.x(), self.x()],
+            (true, Side::Left) => [self.width * 0.5, AREA_WIDTH * 0.5 - self.width * 0.5],
+            (true, Side::Right) => [AREA_WIDTH * 0.5 + self.width * 0.5, AREA_WIDTH - self.width * 0.5],
+        }
+    }
+
+    pub fn scale(&self) -> Vector3<f32> {
+        Vector3::new(self.width / PADDLE_SPRITE_WIDTH, self.height / PADDLE_SPRITE_HEIGHT, 1.0)
     }
 }
 
@@ -83,29 +312,80 @@ impl Component for Paddle {
     type Storage = DenseVecStorage<Self>;
 }
 
-fn initialise_paddles(world: &mut World, sprite_sheet_handle: Handle<SpriteSheet>) {
+#[derive(Default)]
+pub struct Dash {
+    pub remaining: f32,
+    pub cooldown: f32,
+    pub direction: [f32; 2],
+}
+
+impl Component for Dash {
+    type Storage = DenseVecStorage<Self>;
+}
+
+pub struct CooldownBar {
+    pub side: Side,
+}
+
+impl Component for CooldownBar {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// Strip drawn along the end wall where a goal opening is.
+pub struct GoalMarker {
+    pub side: Side,
+}
+
+impl Component for GoalMarker {
+    type Storage = DenseVecStorage<Self>;
+}
+
+fn initialise_paddles(world: &mut World, sprite_sheet_handle: Handle<SpriteSheet>, handicaps: [Handicap; 2]) {
     let sprite_render = SpriteRender::new(sprite_sheet_handle, 0);
 
-    let mut left_transform = Transform::default();
-    let mut right_transform = Transform::default();
+    for (side, handicap) in [Side::Left, Side::Right].iter().zip(handicaps.iter()) {
+        let paddle = Paddle::new(*side, *handicap);
+        let mut transform = Transform::default();
+        transform.set_translation_xyz(paddle.x(), AREA_HEIGHT / 2.0, 0.0);
+        transform.set_scale(paddle.scale());
+
+        world
+            .create_entity()
+            .with(paddle)
+            .with(Dash::default())
+            .with(transform)
+            .with(sprite_render.clone())
+            .build();
+
+        world
+            .create_entity()
+            .with(CooldownBar { side: *side })
+            .with(Transform::default())
+            .with(sprite_render.clone())
+            .with(Hidden)
+            .build();
+    }
+}
 
-    let y = AREA_HEIGHT / 2.0;
-    left_transform.set_translation_xyz(PADDLE_WIDTH * 0.5, y, 0.0);
-    right_transform.set_translation_xyz(AREA_WIDTH - PADDLE_WIDTH * 0.5, y, 0.0);
+fn initialise_goals(world: &mut World, sprite_sheet_handle: Handle<SpriteSheet>) {
+    let (goals, marked) = {
+        let config = world.read_resource::<GameConfig>();
+        (GoalMouths::new(&config), config.mode().goals.is_some())
+    };
 
-    world
-        .create_entity()
-        .with(Paddle::new(Side::Left))
-        .with(left_transform)
-        .with(sprite_render.clone())
-        .build();
+    // Modes without goal mouths score on the whole wall, so there is nothing to mark.
+    if marked {
+        for side in [Side::Left, Side::Right].iter() {
+            world
+                .create_entity()
+                .with(GoalMarker { side: *side })
+                .with(Transform::default())
+                .with(SpriteRender::new(sprite_sheet_handle.clone(), 0))
+                .build();
+        }
+    }
 
-    world
-        .create_entity()
-        .with(Paddle::new(Side::Right))
-        .with(right_transform)
-        .with(sprite_render)
-        .build();
+    world.insert(goals);
 }
 
 fn load_sprite_sheet(world: &mut World) -> Handle<SpriteSheet> {
@@ -128,14 +408,41 @@ fn load_sprite_sheet(world: &mut World) -> Handle<SpriteSheet> {
 pub struct Ball {
     pub id: &'static str,
     pub velocity: [f32; 2],
-    pub original_velocity: [f32; 2],
     pub radius: f32,
+    pub spin: f32,
+    pub hits: u32,
+    pub mass: Option<f32>,
+    pub held: Option<Held>,
+}
+
+/// A ball waiting on a paddle for its serve.
+#[derive(Debug)]
+pub struct Held {
+    pub side: Side,
+    pub elapsed: f32,
 }
 
 impl Ball {
-    pub fn default() -> Self {
-        let velocity = [BALL_VELOCITY_X, BALL_VELOCITY_Y];
-        Self { id: "ball", radius: BALL_RADIUS, velocity: velocity.clone(), original_velocity: velocity }
+    pub fn new(speed: f32) -> Self {
+        let mut ball = Self { id: "ball", radius: BALL_RADIUS, velocity: [BALL_VELOCITY_X, BALL_VELOCITY_Y], spin: 0.0, hits: 0, mass: None, held: None };
+        ball.set_speed(speed);
+        ball
+    }
+
+    pub fn mass(&self) -> f32 {
+        self.mass.unwrap_or(self.radius * self.radius)
+    }
+
+    pub fn speed(&self) -> f32 {
+        self.velocity[0].hypot(self.velocity[1])
+    }
+
+    pub fn set_speed(&mut self, speed: f32) {
+        let current = self.speed();
+        if current > 0.0 {
+            self.velocity[0] *= speed / current;
+            self.velocity[1] *= speed / current;
+        }
     }
 }
 
@@ -152,15 +459,34 @@ pub struct ScoreBoard {
 pub struct ScoreText {
     pub p1_score: Entity,
     pub p2_score: Entity,
+    pub handicaps: [Entity; 2],
+}
+
+impl ScoreText {
+    pub fn show(&self, ui_text: &mut WriteStorage<'_, UiText>, scores: &ScoreBoard) {
+        if let Some(text) = ui_text.get_mut(self.p1_score) {
+            text.text = scores.score_left.to_string();
+        }
+        if let Some(text) = ui_text.get_mut(self.p2_score) {
+            text.text = scores.score_right.to_string();
+        }
+    }
 }
 
-fn initialise_scoreboard(world: &mut World) {
-    let font = world.read_resource::<Loader>().load(
+pub struct NowPlayingText {
+    pub text: Entity,
+}
+
+pub fn load_font(world: &mut World) -> FontHandle {
+    world.read_resource::<Loader>().load(
         "font/square.ttf",
         TtfFormat,
         (),
         &world.read_resource(),
-    );
+    )
+}
+
+fn initialise_scoreboard(world: &mut World, font: FontHandle, scores: &ScoreBoard, handicaps: [Handicap; 2]) {
     let p1_transform = UiTransform::new(
         "P1".to_string(), Anchor::TopMiddle, Anchor::TopMiddle,
         -50., -50., 1., 200., 50.,
@@ -175,7 +501,7 @@ fn initialise_scoreboard(world: &mut World) {
         .with(p1_transform)
         .with(UiText::new(
             font.clone(),
-            "0".to_string(),
+            scores.score_left.to_string(),
             [1., 1., 1., 1.],
             50.,
             LineMode::Single,
@@ -188,7 +514,7 @@ fn initialise_scoreboard(world: &mut World) {
         .with(p2_transform)
         .with(UiText::new(
             font.clone(),
-            "0".to_string(),
+            scores.score_right.to_string(),
             [1., 1., 1., 1.],
             50.,
             LineMode::Single,
@@ -196,5 +522,84 @@ fn initialise_scoreboard(world: &mut World) {
         ))
         .build();
 
-    world.insert(ScoreText { p1_score, p2_score });
+    let handicaps = [
+        initialise_handicap_label(world, font.clone(), "P1_handicap", -125., &handicaps[0]),
+        initialise_handicap_label(world, font, "P2_handicap", 125., &handicaps[1]),
+    ];
+
+    world.insert(ScoreText { p1_score, p2_score, handicaps });
+}
+
+fn initialise_handicap_label(world: &mut World, font: FontHandle, id: &str, x: f32, handicap: &Handicap) -> Entity {
+    let transform = UiTransform::new(
+        id.to_string(), Anchor::TopMiddle, Anchor::TopMiddle,
+        x, -90., 1., 240., 20.,
+    );
+
+    world
+        .create_entity()
+        .with(transform)
+        .with(UiText::new(
+            font,
+            handicap.describe(),
+            [0.7, 0.7, 0.7, 1.],
+            12.,
+            LineMode::Single,
+            Anchor::Middle,
+        ))
+        .build()
+}
+
+fn initialise_countdown(world: &mut World, font: FontHandle, sprite_sheet_handle: Handle<SpriteSheet>) {
+    let transform = UiTransform::new(
+        "countdown".to_string(), Anchor::Middle, Anchor::Middle,
+        0., 0., 1., 200., 120.,
+    );
+
+    let text = world
+        .create_entity()
+        .with(transform)
+        .with(UiText::new(
+            font,
+            String::new(),
+            [1., 1., 1., 1.],
+            100.,
+            LineMode::Single,
+            Anchor::Middle,
+        ))
+        .build();
+
+    let mut marker_transform = Transform::default();
+    marker_transform.set_translation_xyz(AREA_WIDTH / 2.0, AREA_HEIGHT / 2.0, 0.0);
+
+    let marker = world
+        .create_entity()
+        .with(SpriteRender::new(sprite_sheet_handle, 1))
+        .with(marker_transform)
+        .with(Hidden)
+        .build();
+
+    world.insert(CountdownDisplay { text, marker });
+}
+
+pub fn initialise_now_playing(world: &mut World, font: FontHandle) {
+    let transform = UiTransform::new(
+        "now_playing".to_string(), Anchor::BottomMiddle, Anchor::BottomMiddle,
+        0., 20., 1., 480., 30.,
+    );
+
+    let text = world
+        .create_entity()
+        .with(transform)
+        .with(UiText::new(
+            font,
+            String::new(),
+            [1., 1., 1., 0.],
+            18.,
+            LineMode::Single,
+            Anchor::Middle,
+        ))
+        .build();
+
+    world.insert(NowPlayingText { text });
 }
@@ -0,0 +1,370 @@
+use std::path::{Path, PathBuf};
+
+use amethyst::{
+    config::{Config, ConfigError},
+    input::{Bindings, StringBindings},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::pong::Side;
+
+pub struct UserFiles {
+    pub settings: PathBuf,
+    pub bindings: PathBuf,
+}
+
+pub struct DefaultBindings(pub Bindings<StringBindings>);
+
+impl DefaultBindings {
+    /// The saved bindings plus any default axis or action they don't name, so anything added since
+    /// the file was written gets its shipped keys while every saved choice is kept as a whole.
+    pub fn with_overrides(&self, saved: &Bindings<StringBindings>) -> Bindings<StringBindings> {
+        let mut bindings = saved.clone();
+
+        for axis in self.0.axes() {
+            if bindings.axis(axis).is_some() {
+                continue;
+            }
+            if let Some(value) = self.0.axis(axis) {
+                if let Err(error) = bindings.insert_axis(axis.clone(), value.clone()) {
+                    log::warn!("Could not add the default binding for {}: {}", axis, error);
+                }
+            }
+        }
+
+        for action in self.0.actions() {
+            if bindings.actions().any(|name| name == action) {
+                continue;
+            }
+            for combo in self.0.action_bindings(action) {
+                if let Err(error) = bindings.insert_action_binding(action.clone(), combo.iter().cloned()) {
+                    log::warn!("Could not add the default binding for {}: {}", action, error);
+                }
+            }
+        }
+
+        bindings
+    }
+}
+
+pub fn save_user_file<T: Config>(value: &T, path: &Path) -> Result<(), ConfigError> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    value.write(path)
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Settings {
+    pub audio: AudioSettings,
+    pub accessibility: AccessibilitySettings,
+    pub game: GameSettings,
+    pub controls: ControlSettings,
+}
+
+impl Settings {
+    pub fn save(&self, path: &Path) -> Result<(), ConfigError> {
+        save_user_file(self, path)
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct AudioSettings {
+    pub master: f32,
+    pub music: f32,
+    pub sfx: f32,
+    pub music_muted: bool,
+    pub sfx_muted: bool,
+}
+
+impl AudioSettings {
+    pub fn music_volume(&self) -> f32 {
+        if self.music_muted { 0.0 } else { self.master * self.music }
+    }
+
+    pub fn sfx_volume(&self) -> f32 {
+        if self.sfx_muted { 0.0 } else { self.master * self.sfx }
+    }
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self { master: 1.0, music: 0.3, sfx: 1.0, music_muted: false, sfx_muted: false }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct AccessibilitySettings {
+    pub audio_guide: bool,
+    pub player_side: Side,
+}
+
+impl Default for AccessibilitySettings {
+    fn default() -> Self {
+        Self { audio_guide: false, player_side: Side::Left }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Difficulty {
+    Easy, Normal, Hard
+}
+
+impl Difficulty {
+    const ALL: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard];
+
+    pub fn ball_speed_multiplier(self) -> f32 {
+        match self {
+            Difficulty::Easy => 0.8,
+            Difficulty::Normal => 1.0,
+            Difficulty::Hard => 1.25,
+        }
+    }
+
+    pub fn cycle(self, step: i32) -> Self {
+        let index = Self::ALL.iter().position(|difficulty| *difficulty == self).unwrap_or(1) as i32;
+        Self::ALL[(index + step).rem_euclid(Self::ALL.len() as i32) as usize]
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct GameSettings {
+    pub mode: Option<String>,
+    pub difficulty: Difficulty,
+    pub points_to_win: Option<u32>,
+    pub left_handicap: Handicap,
+    pub right_handicap: Handicap,
+}
+
+impl GameSettings {
+    pub fn handicap(&self, side: Side) -> Handicap {
+        match side {
+            Side::Left => self.left_handicap,
+            Side::Right => self.right_handicap,
+        }
+    }
+
+    pub fn handicap_mut(&mut self, side: Side) -> &mut Handicap {
+        match side {
+            Side::Left => &mut self.left_handicap,
+            Side::Right => &mut self.right_handicap,
+        }
+    }
+}
+
+impl Default for GameSettings {
+    fn default() -> Self {
+        Self {
+            mode: None,
+            difficulty: Difficulty::Normal,
+            points_to_win: None,
+            left_handicap: Handicap::default(),
+            right_handicap: Handicap::default(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Handicap {
+    pub paddle_size: f32,
+    pub paddle_speed: f32,
+    pub bonus_points: u32,
+}
+
+impl Handicap {
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if (self.paddle_size - 1.0).abs() > 0.01 {
+            parts.push(format!("size x{:.1}", self.paddle_size));
+        }
+        if (self.paddle_speed - 1.0).abs() > 0.01 {
+            parts.push(format!("speed x{:.1}", self.paddle_speed));
+        }
+        if self.bonus_points > 0 {
+            parts.push(format!("+{} pts", self.bonus_points));
+        }
+        parts.join("  ")
+    }
+}
+
+impl Default for Handicap {
+    fn default() -> Self {
+        Self { paddle_size: 1.0, paddle_speed: 1.0, bonus_points: 0 }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum InputDevice {
+    Keyboard, Mouse
+}
+
+impl InputDevice {
+    pub fn toggle(self) -> Self {
+        match self {
+            InputDevice::Keyboard => InputDevice::Mouse,
+            InputDevice::Mouse => InputDevice::Keyboard,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ControlSettings {
+    pub left: InputDevice,
+    pub right: InputDevice,
+    pub left_profile: InputProfile,
+    pub right_profile: InputProfile,
+}
+
+impl ControlSettings {
+    pub fn device(&self, side: Side) -> InputDevice {
+        match side {
+            Side::Left => self.left,
+            Side::Right => self.right,
+        }
+    }
+
+    pub fn profile(&self, side: Side) -> &InputProfile {
+        match side {
+            Side::Left => &self.left_profile,
+            Side::Right => &self.right_profile,
+        }
+    }
+}
+
+impl Default for ControlSettings {
+    fn default() -> Self {
+        Self {
+            left: InputDevice::Keyboard,
+            right: InputDevice::Keyboard,
+            left_profile: InputProfile::default(),
+            right_profile: InputProfile::default(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub enum SensitivityCurve {
+    Linear,
+    Quadratic,
+    /// `(input, output)` pairs in ascending input order, joined by straight lines; `(0, 0)` is implied.
+    Custom(Vec<(f32, f32)>),
+}
+
+impl SensitivityCurve {
+    fn apply(&self, magnitude: f32) -> f32 {
+        match self {
+            SensitivityCurve::Linear => magnitude,
+            SensitivityCurve::Quadratic => magnitude * magnitude,
+            SensitivityCurve::Custom(points) => {
+                let mut previous = (0.0, 0.0);
+                for &(input, output) in points {
+                    if magnitude <= input {
+                        let span = input - previous.0;
+                        return if span > 0.0 {
+                            previous.1 + (output - previous.1) * (magnitude - previous.0) / span
+                        } else {
+                            output
+                        };
+                    }
+                    previous = (input, output);
+                }
+                previous.1
+            },
+        }
+    }
+}
+
+const SMOOTHING_SNAP: f32 = 0.01;
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct InputProfile {
+    pub dead_zone: f32,
+    pub curve: SensitivityCurve,
+    /// Time constant of the low-pass filter applied to the final stick value.
+    pub smoothing_seconds: Option<f32>,
+    /// Time for a digital key press to build up to full deflection.
+    pub ramp_seconds: Option<f32>,
+}
+
+impl InputProfile {
+    pub fn shape(&self, raw: f32) -> f32 {
+        let magnitude = raw.abs().min(1.0);
+        if magnitude <= self.dead_zone {
+            return 0.0;
+        }
+        let scaled = (magnitude - self.dead_zone) / (1.0 - self.dead_zone).max(std::f32::EPSILON);
+        self.curve.apply(scaled).min(1.0).copysign(raw)
+    }
+
+    pub fn ramp(&self, current: f32, target: f32, delta: f32) -> f32 {
+        let seconds = match self.ramp_seconds {
+            Some(seconds) if seconds > 0.0 => seconds,
+            _ => return target,
+        };
+        if target == 0.0 {
+            return 0.0;
+        }
+
+        let start = if current * target > 0.0 { current } else { 0.0 };
+        let next = start + delta / seconds * target.signum();
+        if target > 0.0 { next.min(target) } else { next.max(target) }
+    }
+
+    pub fn smooth(&self, previous: f32, target: f32, delta: f32) -> f32 {
+        let smoothed = match self.smoothing_seconds {
+            Some(seconds) if seconds > 0.0 => previous + (target - previous) * (1.0 - (-delta / seconds).exp()),
+            _ => target,
+        };
+        // The filter only ever approaches its target, so settle once it is close enough to stop the paddle.
+        if (smoothed - target).abs() < SMOOTHING_SNAP { target } else { smoothed }
+    }
+}
+
+impl Default for InputProfile {
+    fn default() -> Self {
+        Self { dead_zone: 0.1, curve: SensitivityCurve::Linear, smoothing_seconds: None, ramp_seconds: None }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use amethyst::{
+        input::{Bindings, Button, StringBindings},
+        winit::VirtualKeyCode,
+    };
+
+    use super::DefaultBindings;
+
+    fn bind(bindings: &mut Bindings<StringBindings>, action: &str, key: VirtualKeyCode) {
+        bindings.insert_action_binding(action.to_string(), vec![Button::Key(key)]).unwrap();
+    }
+
+    fn keys(bindings: &Bindings<StringBindings>, action: &str) -> Vec<Vec<Button>> {
+        bindings.action_bindings(action).map(|combo| combo.to_vec()).collect()
+    }
+
+    #[test]
+    fn swapped_keys_survive_a_reload() {
+        let mut defaults = Bindings::<StringBindings>::default();
+        bind(&mut defaults, "ServeLeft", VirtualKeyCode::E);
+        bind(&mut defaults, "Quit", VirtualKeyCode::Q);
+        bind(&mut defaults, "DashLeft", VirtualKeyCode::LShift);
+
+        // Saved before DashLeft existed, with Quit moved onto ServeLeft's default key.
+        let mut saved = Bindings::<StringBindings>::default();
+        bind(&mut saved, "ServeLeft", VirtualKeyCode::R);
+        bind(&mut saved, "Quit", VirtualKeyCode::E);
+
+        let merged = DefaultBindings(defaults).with_overrides(&saved);
+        assert_eq!(keys(&merged, "ServeLeft"), vec![vec![Button::Key(VirtualKeyCode::R)]]);
+        assert_eq!(keys(&merged, "Quit"), vec![vec![Button::Key(VirtualKeyCode::E)]]);
+        assert_eq!(keys(&merged, "DashLeft"), vec![vec![Button::Key(VirtualKeyCode::LShift)]]);
+    }
+}
@@ -1,40 +1,92 @@
 use amethyst::{
     assets::AssetStorage,
-    audio::{output::Output, Source},
-    ecs::{Read, ReadExpect, System, SystemData, World},
+    audio::Source,
+    core::timing::Time,
+    ecs::{Read, ReadExpect, System, SystemData, World, WriteExpect},
     shrev::{EventChannel, ReaderId},
     core::SystemDesc,
 };
 
-use crate::audio::{SoundEvent, Sounds, play_sound};
+use crate::audio::{sound_request, SoundOutput, Sounds};
+use crate::config::GameConfig;
+use crate::pong::PongEvent;
+use crate::settings::Settings;
 
 #[derive(Default)]
 pub struct AudioSystemDesc;
 
 impl <'s, 'f> SystemDesc<'s, 'f, AudioSystem> for AudioSystemDesc {
     fn build(self, world: &mut World) -> AudioSystem {
+        SoundOutput::setup(world);
         <AudioSystem as System<'_>>::SystemData::setup(world);
-        let reader_id = world.fetch_mut::<EventChannel<SoundEvent>>().register_reader();
+        let reader_id = world.fetch_mut::<EventChannel<PongEvent>>().register_reader();
         AudioSystem { reader_id }
     }
 }
 
 pub struct AudioSystem {
-    reader_id: ReaderId<SoundEvent>
+    reader_id: ReaderId<PongEvent>,
 }
 
 impl <'s> System<'s> for AudioSystem {
 
     type SystemData = (
-        Read<'s, EventChannel<SoundEvent>>,
+        Read<'s, EventChannel<PongEvent>>,
         Read<'s, AssetStorage<Source>>,
         ReadExpect<'s, Sounds>,
-        Option<Read<'s, Output>>,
+        Read<'s, Settings>,
+        ReadExpect<'s, GameConfig>,
+        Read<'s, Time>,
+        WriteExpect<'s, SoundOutput>,
     );
 
-    fn run(&mut self, (event_channel, asset_storage, sounds, output): Self::SystemData) {
+    fn run(&mut self, (event_channel, asset_storage, sounds, settings, config, time, mut sound_output): Self::SystemData) {
+        let reference_speed = config.mode().ball_speed.max;
+        let volume = settings.audio.sfx_volume();
+
         for event in event_channel.read(&mut self.reader_id) {
-            play_sound(&*sounds, event, &asset_storage, output.as_deref());
+            if let Some(request) = sound_request(&sounds, &asset_storage, event, volume, reference_speed, time.absolute_time_seconds()) {
+                sound_output.0.play(&request, &asset_storage);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use amethyst::{
+        core::SystemDesc,
+        ecs::{RunNow, World, WorldExt},
+        shrev::EventChannel,
+    };
+
+    use super::AudioSystemDesc;
+    use crate::audio::{Blip, PlayLog, SoundCue, SoundEntry, SoundTable, Sounds};
+    use crate::config::GameConfig;
+    use crate::pong::{PongEvent, Side};
+
+    #[test]
+    fn one_score_plays_one_score_sound() {
+        let mut table = SoundTable::default();
+        for cue in [SoundCue::Score, SoundCue::WallBounce, SoundCue::MatchWon].iter() {
+            table.cues.insert(*cue, SoundEntry { synth: Some(Blip::default()), ..SoundEntry::default() });
         }
+
+        let mut world = World::new();
+        world.insert(Sounds::synth_only(&table));
+        world.insert(GameConfig::default());
+
+        // A `PlayLog` in the world makes the system record requests instead of playing them.
+        let play_log = PlayLog::default();
+        world.insert(play_log.clone());
+        let mut system = AudioSystemDesc::default().build(&mut world);
+        world
+            .write_resource::<EventChannel<PongEvent>>()
+            .single_write(PongEvent::Score { side: Side::Left, position: [90.0, 50.0], speed: 90.0 });
+        system.run_now(&world);
+
+        let requests = play_log.0.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].cue, Some(SoundCue::Score));
     }
 }
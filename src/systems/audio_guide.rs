@@ -0,0 +1,169 @@
+use amethyst::{
+    assets::AssetStorage,
+    audio::Source,
+    core::{SystemDesc, timing::Time, transform::Transform},
+    ecs::{Join, Read, ReadStorage, System, SystemData, World, WriteExpect},
+    shrev::{EventChannel, ReaderId},
+};
+
+use crate::audio::{pan_for, Blip, PlayRequest, SoundData, SoundOutput, ToneSink, Waveform};
+use crate::pong::{Ball, Paddle, PongEvent, ScoreBoard, Side, AREA_HEIGHT};
+use crate::settings::Settings;
+
+const BALL_TONE_SEGMENT: f32 = 0.08;
+const BALL_TONE_LOW: f32 = 220.0;
+const BALL_TONE_RANGE: f32 = 660.0;
+const PADDLE_PING_INTERVAL: f32 = 0.4;
+const ANNOUNCE_DELAY: f32 = 0.4;
+const ANNOUNCE_GAP: f32 = 0.18;
+
+#[derive(Default)]
+pub struct AudioGuideSystemDesc;
+
+impl <'s, 'f> SystemDesc<'s, 'f, AudioGuideSystem> for AudioGuideSystemDesc {
+    fn build(self, world: &mut World) -> AudioGuideSystem {
+        SoundOutput::setup(world);
+        <AudioGuideSystem as System<'_>>::SystemData::setup(world);
+        let reader_id = world.fetch_mut::<EventChannel<PongEvent>>().register_reader();
+        AudioGuideSystem { reader_id, ball_tone: None, paddle_timer: 0.0, pending: Vec::new() }
+    }
+}
+
+struct Beep {
+    delay: f32,
+    blip: Blip,
+    pan: f32,
+}
+
+pub struct AudioGuideSystem {
+    reader_id: ReaderId<PongEvent>,
+    ball_tone: Option<Box<dyn ToneSink>>,
+    paddle_timer: f32,
+    pending: Vec<Beep>,
+}
+
+impl AudioGuideSystem {
+    /// Left then right, each panned to its side: a buzz for zero, otherwise one long low beep
+    /// per ten followed by one short beep per remaining point, so 23 is two long and three short.
+    fn announce_score(&mut self, left: u32, right: u32) {
+        let mut delay = ANNOUNCE_DELAY;
+        for (points, frequency, pan) in [(left, 330.0, -0.8), (right, 550.0, 0.8)].iter() {
+            if *points == 0 {
+                self.pending.push(Beep { delay, blip: beep(Waveform::Square, 150.0, 0.05), pan: *pan });
+                delay += ANNOUNCE_GAP;
+            }
+            for _ in 0..*points / 10 {
+                self.pending.push(Beep { delay, blip: beep(Waveform::Sine, *frequency * 0.5, 0.3), pan: *pan });
+                delay += ANNOUNCE_GAP * 2.0;
+            }
+            for _ in 0..*points % 10 {
+                self.pending.push(Beep { delay, blip: beep(Waveform::Sine, *frequency, 0.1), pan: *pan });
+                delay += ANNOUNCE_GAP;
+            }
+            delay += ANNOUNCE_GAP * 2.0;
+        }
+    }
+
+    fn announce_winner(&mut self, side: Side) {
+        let pan = match side { Side::Left => -0.8, Side::Right => 0.8 };
+        for (index, frequency) in [440.0, 554.0, 659.0, 880.0].iter().enumerate() {
+            self.pending.push(Beep { delay: ANNOUNCE_DELAY + index as f32 * ANNOUNCE_GAP, blip: beep(Waveform::Sine, *frequency, 0.15), pan });
+        }
+    }
+}
+
+impl <'s> System<'s> for AudioGuideSystem {
+    type SystemData = (
+        Read<'s, EventChannel<PongEvent>>,
+        Read<'s, Settings>,
+        Read<'s, ScoreBoard>,
+        ReadStorage<'s, Ball>,
+        ReadStorage<'s, Paddle>,
+        ReadStorage<'s, Transform>,
+        WriteExpect<'s, SoundOutput>,
+        Read<'s, AssetStorage<Source>>,
+        Read<'s, Time>,
+    );
+
+    fn run(&mut self, (event_channel, settings, scores, balls, paddles, transforms, mut sound_output, storage, time): Self::SystemData) {
+        let mut scored = false;
+        let mut winner = None;
+        for event in event_channel.read(&mut self.reader_id) {
+            match event {
+                PongEvent::Score { .. } => scored = true,
+                PongEvent::MatchWon(side) => winner = Some(*side),
+                _ => {},
+            }
+        }
+
+        let guide = &settings.accessibility;
+        if !guide.audio_guide {
+            self.ball_tone = None;
+            self.pending.clear();
+            return;
+        }
+        let backend = &mut sound_output.0;
+        let delta = time.delta_seconds();
+        let timestamp = time.absolute_time_seconds();
+        let volume = settings.audio.sfx_volume();
+
+        match (&balls, &transforms).join().next() {
+            Some((_, transform)) => {
+                let sink = self.ball_tone.get_or_insert_with(|| backend.open_tone());
+                sink.set_volume(volume * 0.5);
+
+                if sink.is_empty() {
+                    let height = (transform.translation().y / AREA_HEIGHT).max(0.0).min(1.0);
+                    let segment = PlayRequest {
+                        cue: None,
+                        sound_id: "guide:ball".to_string(),
+                        data: SoundData::Tone { waveform: Waveform::Sine, frequency: BALL_TONE_LOW + BALL_TONE_RANGE * height, duration: BALL_TONE_SEGMENT },
+                        volume: 1.0,
+                        pan: pan_for(transform.translation().x),
+                        pitch: 1.0,
+                        timestamp,
+                    };
+                    sink.queue(&segment);
+                    sink.queue(&segment);
+                }
+            },
+            None => self.ball_tone = None,
+        }
+
+        self.paddle_timer -= delta;
+        if self.paddle_timer <= 0.0 {
+            self.paddle_timer = PADDLE_PING_INTERVAL;
+
+            let own_paddle = (&paddles, &transforms).join().find(|(paddle, _)| paddle.side == guide.player_side);
+            if let Some((_, transform)) = own_paddle {
+                let height = (transform.translation().y / AREA_HEIGHT).max(0.0).min(1.0);
+                let blip = beep(Waveform::Square, 110.0 + 220.0 * height, 0.05);
+                backend.play(&beep_request(blip, pan_for(transform.translation().x), volume * 0.6, timestamp), &storage);
+            }
+        }
+
+        if let Some(side) = winner {
+            self.pending.clear();
+            self.announce_winner(side);
+        } else if scored {
+            self.pending.clear();
+            self.announce_score(scores.score_left as u32, scores.score_right as u32);
+        }
+
+        for beep in self.pending.iter_mut() {
+            beep.delay -= delta;
+            if beep.delay <= 0.0 {
+                backend.play(&beep_request(beep.blip.clone(), beep.pan, volume, timestamp), &storage);
+            }
+        }
+        self.pending.retain(|beep| beep.delay > 0.0);
+    }
+}
+
+fn beep(waveform: Waveform, frequency: f32, duration: f32) -> Blip {
+    Blip { waveform, frequency, duration, ..Blip::default() }
+}
+
+fn beep_request(blip: Blip, pan: f32, volume: f32, timestamp: f64) -> PlayRequest {
+    PlayRequest { cue: None, sound_id: "guide:beep".to_string(), data: SoundData::Synth(blip, 0.0), volume, pan, pitch: 1.0, timestamp }
+}
@@ -1,12 +1,13 @@
 use amethyst::{
-    ecs::{Join, Entities, Read, WriteStorage, ReadExpect, System, SystemData, World},
+    ecs::{Join, Entities, ReadStorage, WriteStorage, ReadExpect, System, SystemData, World, Write},
     assets::{Handle},
     shrev::{EventChannel, ReaderId},
     core::{SystemDesc, transform::Transform, timing::Time},
     renderer::{SpriteRender, SpriteSheet},
 };
 
-use crate::pong::{PongEvent, Ball, SpriteSheetHandler, AREA_WIDTH, AREA_HEIGHT};
+use crate::config::{GameConfig, ServeStyle};
+use crate::pong::{PongEvent, Ball, Held, MatchRules, Paddle, ServeCountdown, Side, SpriteSheetHandler, AREA_WIDTH, AREA_HEIGHT, BALL_RADIUS};
 
 #[derive(Default)]
 pub struct BallSystemDesc;
@@ -15,61 +16,82 @@ impl <'s, 'f> SystemDesc<'s, 'f, BallSystem> for BallSystemDesc {
     fn build(self, world: &mut World) -> BallSystem {
         <BallSystem as System>::SystemData::setup(world);
         let reader_id = world.fetch_mut::<EventChannel<PongEvent>>().register_reader();
+        let remaining = world.read_resource::<GameConfig>().serve.countdown_seconds();
+        world.insert(ServeCountdown { remaining: Some(remaining), position: [AREA_WIDTH / 2.0, AREA_HEIGHT / 2.0] });
 
-        BallSystem { reader_id, ball_spawn_timer: Some(1.0) }
+        BallSystem { reader_id, server: Side::Left }
     }
 }
 
 pub struct BallSystem {
     pub reader_id : ReaderId<PongEvent>,
-    ball_spawn_timer: Option<f32>,
+    server: Side,
 }
 
 impl <'s> System<'s> for BallSystem {
     type SystemData = (
-        Read<'s, EventChannel<PongEvent>>,
+        Write<'s, EventChannel<PongEvent>>,
         Entities<'s>,
         WriteStorage<'s, Ball>,
         WriteStorage<'s, Transform>,
         WriteStorage<'s, SpriteRender>,
         ReadExpect<'s, SpriteSheetHandler>,
         ReadExpect<'s, Time>,
+        ReadExpect<'s, GameConfig>,
+        ReadExpect<'s, MatchRules>,
+        ReadStorage<'s, Paddle>,
+        Write<'s, ServeCountdown>,
     );
 
-    fn run(&mut self, (event_channel, entities, mut balls, mut transform, mut sprites, sprite_sheet_handler, time): Self::SystemData) {
+    fn run(&mut self, (mut event_channel, entities, mut balls, mut transform, mut sprites, sprite_sheet_handler, time, config, rules, paddles, mut countdown): Self::SystemData) {
         for event in event_channel.read(&mut self.reader_id) {
             match event {
-                PongEvent::Score => {
+                PongEvent::Score { side, .. } => {
+                    self.server = side.opposite();
                     for (entity, _ball) in (&*entities, &mut balls).join() {
                         match entities.delete(entity) {
                             Ok(_) => {},
                             Err(error) => panic!("Something when wrong {}", error),
                         };
-                        self.ball_spawn_timer.replace(1.0);
                     }
+                    countdown.remaining = Some(config.serve.countdown_seconds());
                 },
                 _ => {},
             };
         }
 
-        if let Some(mut timer) = self.ball_spawn_timer.take() {
-            {
-                timer -= time.delta_seconds();
-            }
+        if let Some(remaining) = countdown.remaining {
+            let remaining = remaining - time.delta_seconds();
+            let held_serve = match config.serve.style {
+                ServeStyle::Player => (&paddles, &transform)
+                    .join()
+                    .find(|(paddle, _)| paddle.side == self.server)
+                    .map(|(paddle, local)| paddle.serve_position([local.translation().x, local.translation().y], BALL_RADIUS)),
+                ServeStyle::Automatic => None,
+            };
+            let position = held_serve.unwrap_or([AREA_WIDTH / 2.0, AREA_HEIGHT / 2.0]);
+            countdown.position = position;
+
+            if remaining <= 0.0 {
+                countdown.remaining = None;
 
-            if timer <= 0.0 {
-                initialise_ball(&entities, &mut balls, &mut transform, &mut sprites, sprite_sheet_handler.sprite_sheet_handler.clone());
-                self.ball_spawn_timer.take();
+                let mut ball = Ball::new(config.mode().ball_speed.speed_after(0, rules.speed_multiplier));
+                if held_serve.is_some() {
+                    ball.held = Some(Held { side: self.server, elapsed: 0.0 });
+                } else {
+                    event_channel.single_write(PongEvent::Serve { position });
+                }
+                initialise_ball(&entities, &mut balls, &mut transform, &mut sprites, sprite_sheet_handler.sprite_sheet_handler.clone(), ball, position);
             } else {
-                self.ball_spawn_timer.replace(timer);
+                countdown.remaining = Some(remaining);
             }
         }
     }
 }
 
-fn initialise_ball<'s>(entities: &Entities, balls: &mut WriteStorage<'s, Ball>, locals: &mut WriteStorage<'s, Transform>, sprites: &mut WriteStorage<'s, SpriteRender>, sprite_sheet_handler: Handle<SpriteSheet>) {
+fn initialise_ball<'s>(entities: &Entities, balls: &mut WriteStorage<'s, Ball>, locals: &mut WriteStorage<'s, Transform>, sprites: &mut WriteStorage<'s, SpriteRender>, sprite_sheet_handler: Handle<SpriteSheet>, ball: Ball, position: [f32; 2]) {
     let mut local_transform = Transform::default();
-    local_transform.set_translation_xyz(AREA_WIDTH / 2.0, AREA_HEIGHT / 2.0, 0.0);
+    local_transform.set_translation_xyz(position[0], position[1], 0.0);
 
     let sprite_render = SpriteRender::new(sprite_sheet_handler, 1);
 
@@ -77,6 +99,6 @@ fn initialise_ball<'s>(entities: &Entities, balls: &mut WriteStorage<'s, Ball>,
         .build_entity()
         .with(sprite_render, sprites)
         .with(local_transform, locals)
-        .with(Ball::default(), balls)
+        .with(ball, balls)
         .build();
 }
@@ -1,72 +1,136 @@
 use amethyst::{
     core::Transform,
-    core::timing::Time,
-    ecs::{Join, ReadStorage, ReadExpect, System, WriteStorage, Write},
+    derive::SystemDesc,
+    ecs::{Join, Read, ReadStorage, ReadExpect, System, SystemData, WriteStorage, Write},
     shrev::EventChannel,
 };
 
-use crate::pong::{Ball, Paddle, AREA_HEIGHT, PongEvent};
-use crate::audio::SoundEvent;
+use crate::config::GameConfig;
+use crate::pong::{Ball, GoalMouths, Paddle, PongEvent, Side, AREA_HEIGHT, AREA_WIDTH};
 
-pub struct BounceSystem {
-    last_bounce: f32,
-}
-
-impl BounceSystem {
-    pub fn new() -> Self {
-        Self { last_bounce: 0.5 }
-    }
-}
+#[derive(SystemDesc)]
+pub struct BounceSystem;
 
 impl <'s> System<'s> for BounceSystem {
     type SystemData = (
         WriteStorage<'s, Ball>,
         ReadStorage<'s, Paddle>,
-        ReadStorage<'s, Transform>,
-        ReadExpect<'s, Time>,
-        Write<'s, EventChannel<SoundEvent>>,
+        WriteStorage<'s, Transform>,
+        ReadExpect<'s, GameConfig>,
+        Read<'s, GoalMouths>,
         Write<'s, EventChannel<PongEvent>>,
     );
 
-    fn run(&mut self, (mut balls, paddles, transforms, time, mut event_channel, mut pong_event_channel): Self::SystemData) {
-        if self.last_bounce > 0. {
-            self.last_bounce -= time.delta_seconds();
-        }
+    fn run(&mut self, (mut balls, paddles, mut transforms, config, goals, mut pong_event_channel): Self::SystemData) {
+        let paddle_positions = (&paddles, &transforms)
+            .join()
+            .map(|(paddle, transform)| (paddle, [transform.translation().x, transform.translation().y]))
+            .collect::<Vec<_>>();
+
+        for (ball, transform) in (&mut balls, &mut transforms).join() {
+            if ball.held.is_some() {
+                continue;
+            }
 
-        for (ball, transform) in (&mut balls, &transforms).join() {
-            let ball_x = transform.translation().x;
-            let ball_y = transform.translation().y;
+            let mut position = [transform.translation().x, transform.translation().y];
             let ball_y_velocity = ball.velocity[1];
 
-            if (ball_y <= ball.radius && ball_y_velocity < 0.0) || (ball_y >= AREA_HEIGHT - ball.radius && ball_y_velocity > 0.0) {
+            let hit_bottom = position[1] <= ball.radius && ball_y_velocity < 0.0;
+            let hit_top = position[1] >= AREA_HEIGHT - ball.radius && ball_y_velocity > 0.0;
+
+            if hit_bottom || hit_top {
+                let wall_side = if hit_top { 1.0 } else { -1.0 };
+
+                // The kick only turns the ball; it leaves the wall as fast as it arrived.
+                let speed = ball.speed();
                 ball.velocity[1] = -ball.velocity[1];
+                ball.velocity[0] += wall_side * ball.spin * config.spin.wall_kick;
+                ball.set_speed(speed);
+                ball.spin *= config.spin.wall_retention;
+
+                pong_event_channel.single_write(PongEvent::WallBounce { position, speed: ball.speed() });
             }
+            position[1] = position[1].max(ball.radius).min(AREA_HEIGHT - ball.radius);
 
-            for (paddle, paddle_transform) in (&paddles, &transforms).join() {
-                let paddle_x = paddle_transform.translation().x - (paddle.width * 0.5);
-                let paddle_y = paddle_transform.translation().y - (paddle.height * 0.5);
-
-                if point_in_rect(
-                    ball_x, ball_y,
-                    paddle_x - ball.radius, paddle_y - ball.radius,
-                    paddle_x + paddle.width + ball.radius,
-                    paddle_y + paddle.height + ball.radius
-                ) {
-                    if self.last_bounce > 0.0 {
-                        return
-                    }
+            // Either side of a goal mouth the end wall is solid.
+            let left_open = goals.contains(Side::Left, position[1]);
+            let right_open = goals.contains(Side::Right, position[1]);
+            let hit_left = !left_open && position[0] <= ball.radius && ball.velocity[0] < 0.0;
+            let hit_right = !right_open && position[0] >= AREA_WIDTH - ball.radius && ball.velocity[0] > 0.0;
+
+            if hit_left || hit_right {
+                ball.velocity[0] = -ball.velocity[0];
+                pong_event_channel.single_write(PongEvent::WallBounce { position, speed: ball.speed() });
+            }
+            if !left_open {
+                position[0] = position[0].max(ball.radius);
+            }
+            if !right_open {
+                position[0] = position[0].min(AREA_WIDTH - ball.radius);
+            }
+
+            for (paddle, paddle_position) in &paddle_positions {
+                let contact = circle_rect_contact(
+                    position, ball.radius,
+                    *paddle_position, [paddle.width * 0.5, paddle.height * 0.5],
+                );
+
+                if let Some(Contact { normal, depth }) = contact {
+                    position[0] += normal[0] * depth;
+                    position[1] += normal[1] * depth;
+
+                    let approach_speed = (ball.velocity[0] - paddle.velocity[0]) * normal[0]
+                        + (ball.velocity[1] - paddle.velocity[1]) * normal[1];
 
-                    ball.velocity[0] = -ball.velocity[0];
-                    event_channel.single_write(SoundEvent::Bounce);
-                    pong_event_channel.single_write(PongEvent::Bounce(ball.id));
+                    if approach_speed < 0.0 {
+                        ball.velocity[0] -= 2.0 * approach_speed * normal[0];
+                        ball.velocity[1] -= 2.0 * approach_speed * normal[1];
+                        ball.velocity[1] += paddle.velocity[1] * config.paddle.english;
+                        ball.spin = (ball.spin + paddle.velocity[1] * config.spin.paddle_transfer)
+                            .min(config.spin.max_spin)
+                            .max(-config.spin.max_spin);
 
-                    self.last_bounce = 0.5;
+                        pong_event_channel.single_write(PongEvent::Bounce {
+                            ball: ball.id, side: paddle.side, position, speed: ball.speed(),
+                        });
+                    }
                 }
             }
+
+            transform.set_translation_x(position[0]);
+            transform.set_translation_y(position[1]);
         }
     }
 }
 
-fn point_in_rect(x: f32, y: f32, left: f32, bottom: f32, right: f32, top: f32) -> bool {
-    x >= left && x <= right && y >= bottom && y <= top
+struct Contact {
+    normal: [f32; 2],
+    depth: f32,
+}
+
+fn circle_rect_contact(center: [f32; 2], radius: f32, rect_center: [f32; 2], half_extents: [f32; 2]) -> Option<Contact> {
+    let offset = [center[0] - rect_center[0], center[1] - rect_center[1]];
+    let closest = [
+        offset[0].max(-half_extents[0]).min(half_extents[0]),
+        offset[1].max(-half_extents[1]).min(half_extents[1]),
+    ];
+    let delta = [offset[0] - closest[0], offset[1] - closest[1]];
+    let distance = delta[0].hypot(delta[1]);
+
+    if distance > 0.0 {
+        if distance >= radius {
+            return None;
+        }
+        return Some(Contact { normal: [delta[0] / distance, delta[1] / distance], depth: radius - distance });
+    }
+
+    // The centre is inside the paddle, so leave through the shallowest face.
+    let overlap_x = half_extents[0] - offset[0].abs();
+    let overlap_y = half_extents[1] - offset[1].abs();
+
+    if overlap_x < overlap_y {
+        Some(Contact { normal: [offset[0].signum(), 0.0], depth: overlap_x + radius })
+    } else {
+        Some(Contact { normal: [0.0, offset[1].signum()], depth: overlap_y + radius })
+    }
 }
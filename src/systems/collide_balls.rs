@@ -0,0 +1,100 @@
+use amethyst::{
+    core::Transform,
+    derive::SystemDesc,
+    ecs::{Entities, Entity, Join, System, SystemData, Write, WriteStorage},
+    shrev::EventChannel,
+};
+
+use crate::pong::{Ball, PongEvent};
+
+#[derive(SystemDesc)]
+pub struct BallCollisionSystem;
+
+impl <'s> System<'s> for BallCollisionSystem {
+    type SystemData = (
+        Entities<'s>,
+        WriteStorage<'s, Ball>,
+        WriteStorage<'s, Transform>,
+        Write<'s, EventChannel<PongEvent>>,
+    );
+
+    fn run(&mut self, (entities, mut balls, mut transforms, mut pong_event_channel): Self::SystemData) {
+        let mut bodies = (&*entities, &balls, &transforms)
+            .join()
+            .filter(|(_, ball, _)| ball.held.is_none())
+            .map(|(entity, ball, transform)| Body {
+                entity,
+                position: [transform.translation().x, transform.translation().y],
+                velocity: ball.velocity,
+                radius: ball.radius,
+                inverse_mass: 1.0 / ball.mass(),
+            })
+            .collect::<Vec<_>>();
+
+        if bodies.len() < 2 {
+            return;
+        }
+
+        for i in 0..bodies.len() {
+            for j in (i + 1)..bodies.len() {
+                let (head, tail) = bodies.split_at_mut(j);
+                if collide(&mut head[i], &mut tail[0]) {
+                    let (a, b) = (&head[i], &tail[0]);
+                    let position = [(a.position[0] + b.position[0]) * 0.5, (a.position[1] + b.position[1]) * 0.5];
+                    let speed = (a.velocity[0] - b.velocity[0]).hypot(a.velocity[1] - b.velocity[1]);
+
+                    pong_event_channel.single_write(PongEvent::BallCollision { balls: [a.entity, b.entity], position, speed });
+                }
+            }
+        }
+
+        for body in bodies {
+            if let Some(ball) = balls.get_mut(body.entity) {
+                ball.velocity = body.velocity;
+            }
+            if let Some(transform) = transforms.get_mut(body.entity) {
+                transform.set_translation_x(body.position[0]);
+                transform.set_translation_y(body.position[1]);
+            }
+        }
+    }
+}
+
+struct Body {
+    entity: Entity,
+    position: [f32; 2],
+    velocity: [f32; 2],
+    radius: f32,
+    inverse_mass: f32,
+}
+
+fn collide(a: &mut Body, b: &mut Body) -> bool {
+    let delta = [b.position[0] - a.position[0], b.position[1] - a.position[1]];
+    let distance = delta[0].hypot(delta[1]);
+    let overlap = a.radius + b.radius - distance;
+
+    if overlap <= 0.0 {
+        return false;
+    }
+
+    let normal = if distance > 0.0 { [delta[0] / distance, delta[1] / distance] } else { [1.0, 0.0] };
+    let total_inverse_mass = a.inverse_mass + b.inverse_mass;
+
+    let a_share = overlap * a.inverse_mass / total_inverse_mass;
+    let b_share = overlap * b.inverse_mass / total_inverse_mass;
+    a.position = [a.position[0] - normal[0] * a_share, a.position[1] - normal[1] * a_share];
+    b.position = [b.position[0] + normal[0] * b_share, b.position[1] + normal[1] * b_share];
+
+    let approach_speed = (a.velocity[0] - b.velocity[0]) * normal[0] + (a.velocity[1] - b.velocity[1]) * normal[1];
+    if approach_speed <= 0.0 {
+        return false;
+    }
+
+    let impulse = 2.0 * approach_speed / total_inverse_mass;
+    let a_change = impulse * a.inverse_mass;
+    let b_change = impulse * b.inverse_mass;
+    a.velocity = [a.velocity[0] - normal[0] * a_change, a.velocity[1] - normal[1] * a_change];
+    b.velocity = [b.velocity[0] + normal[0] * b_change, b.velocity[1] + normal[1] * b_change];
+
+    true
+}
@@ -0,0 +1,62 @@
+use amethyst::{
+    core::{Hidden, Transform},
+    ecs::{Read, ReadExpect, System, SystemData, Write, WriteStorage},
+    shrev::EventChannel,
+    ui::UiText,
+};
+
+use crate::config::GameConfig;
+use crate::pong::{CountdownDisplay, PongEvent, ServeCountdown};
+
+const MARKER_FLASHES_PER_SECOND: f32 = 4.0;
+
+#[derive(Default)]
+pub struct CountdownSystem {
+    shown: Option<u32>,
+}
+
+impl <'s> System<'s> for CountdownSystem {
+    type SystemData = (
+        Read<'s, ServeCountdown>,
+        ReadExpect<'s, GameConfig>,
+        ReadExpect<'s, CountdownDisplay>,
+        WriteStorage<'s, UiText>,
+        WriteStorage<'s, Hidden>,
+        WriteStorage<'s, Transform>,
+        Write<'s, EventChannel<PongEvent>>,
+    );
+
+    fn run(&mut self, (countdown, config, display, mut ui_text, mut hidden, mut transforms, mut pong_event_channel): Self::SystemData) {
+        let serve = &config.serve;
+        let step = match countdown.remaining {
+            Some(remaining) if serve.countdown > 0 => {
+                let step = (remaining / serve.countdown_step.max(std::f32::EPSILON)).ceil() as u32;
+                Some(step.max(1).min(serve.countdown))
+            },
+            _ => None,
+        };
+
+        if step != self.shown {
+            self.shown = step;
+            if step.is_some() {
+                pong_event_channel.single_write(PongEvent::CountdownTick { position: countdown.position });
+            }
+            if let Some(text) = ui_text.get_mut(display.text) {
+                text.text = step.map_or_else(String::new, |step| step.to_string());
+            }
+        }
+
+        let marker_lit = countdown.remaining.map_or(false, |remaining| (remaining * MARKER_FLASHES_PER_SECOND) as u32 % 2 == 0);
+        if marker_lit {
+            hidden.remove(display.marker);
+            if let Some(transform) = transforms.get_mut(display.marker) {
+                transform.set_translation_x(countdown.position[0]);
+                transform.set_translation_y(countdown.position[1]);
+            }
+        } else if !hidden.contains(display.marker) {
+            if let Err(error) = hidden.insert(display.marker, Hidden) {
+                log::warn!("Failed to hide the serve marker: {}", error);
+            }
+        }
+    }
+}
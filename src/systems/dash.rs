@@ -0,0 +1,84 @@
+use amethyst::{
+    core::{math::Vector3, timing::Time, Hidden, Transform},
+    ecs::{Entities, Join, Read, ReadExpect, ReadStorage, System, SystemData, WriteStorage},
+    input::{InputHandler, StringBindings},
+};
+
+use crate::config::GameConfig;
+use crate::pong::{CooldownBar, Dash, Paddle, Side, PADDLE_SPRITE_HEIGHT, PADDLE_SPRITE_WIDTH};
+
+const BAR_WIDTH: f32 = 1.0;
+
+#[derive(Default)]
+pub struct DashSystem {
+    /// Whether each side's dash key was down last frame, so holding it only dashes once.
+    was_down: [bool; 2],
+}
+
+impl <'s> System<'s> for DashSystem {
+    type SystemData = (
+        Entities<'s>,
+        WriteStorage<'s, Dash>,
+        ReadStorage<'s, Paddle>,
+        ReadStorage<'s, CooldownBar>,
+        WriteStorage<'s, Transform>,
+        WriteStorage<'s, Hidden>,
+        Read<'s, InputHandler<StringBindings>>,
+        Read<'s, Time>,
+        ReadExpect<'s, GameConfig>,
+    );
+
+    fn run(&mut self, (entities, mut dashes, paddles, bars, mut transforms, mut hidden, input, time, config): Self::SystemData) {
+        let config = &config.paddle.dash;
+        let delta = time.delta_seconds();
+        let mut cooling = Vec::new();
+
+        for (dash, paddle, transform) in (&mut dashes, &paddles, &transforms).join() {
+            dash.remaining = (dash.remaining - delta).max(0.0);
+            dash.cooldown = (dash.cooldown - delta).max(0.0);
+
+            let (action, index) = match paddle.side {
+                Side::Left => ("DashLeft", 0),
+                Side::Right => ("DashRight", 1),
+            };
+            let down = input.action_is_down(action).unwrap_or(false);
+            let pressed = down && !self.was_down[index];
+            self.was_down[index] = down;
+
+            let heading = if paddle.input[0] != 0.0 || paddle.input[1] != 0.0 { paddle.input } else { paddle.velocity };
+            let length = heading[0].hypot(heading[1]);
+
+            if pressed && dash.cooldown <= 0.0 && length > 0.0 {
+                dash.direction = [heading[0] / length, heading[1] / length];
+                dash.remaining = config.duration;
+                dash.cooldown = config.cooldown;
+            }
+
+            if dash.cooldown > 0.0 {
+                let recovered = 1.0 - dash.cooldown / config.cooldown.max(std::f32::EPSILON);
+                let offset = paddle.width * 0.5 + BAR_WIDTH;
+                let x = match paddle.side {
+                    Side::Left => transform.translation().x + offset,
+                    Side::Right => transform.translation().x - offset,
+                };
+                cooling.push((paddle.side, [x, transform.translation().y], paddle.height * recovered));
+            }
+        }
+
+        for (entity, bar, transform) in (&*entities, &bars, &mut transforms).join() {
+            match cooling.iter().find(|(side, _, _)| *side == bar.side) {
+                Some((_, position, height)) => {
+                    hidden.remove(entity);
+                    transform.set_translation_xyz(position[0], position[1], 0.0);
+                    transform.set_scale(Vector3::new(BAR_WIDTH / PADDLE_SPRITE_WIDTH, height / PADDLE_SPRITE_HEIGHT, 1.0));
+                },
+                None if !hidden.contains(entity) => {
+                    if let Err(error) = hidden.insert(entity, Hidden) {
+                        log::warn!("Failed to hide a cooldown bar: {}", error);
+                    }
+                },
+                None => {},
+            }
+        }
+    }
+}
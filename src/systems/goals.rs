@@ -0,0 +1,53 @@
+use amethyst::{
+    core::{math::Vector3, timing::Time, Transform},
+    ecs::{Join, Read, ReadExpect, ReadStorage, System, SystemData, Write, WriteStorage},
+};
+
+use crate::config::GameConfig;
+use crate::pong::{GoalMarker, GoalMouths, Side, AREA_HEIGHT, AREA_WIDTH, PADDLE_SPRITE_HEIGHT, PADDLE_SPRITE_WIDTH};
+
+const MARKER_WIDTH: f32 = 1.0;
+
+#[derive(Default)]
+pub struct GoalSystem {
+    elapsed: f32,
+}
+
+impl <'s> System<'s> for GoalSystem {
+    type SystemData = (
+        Write<'s, GoalMouths>,
+        ReadStorage<'s, GoalMarker>,
+        WriteStorage<'s, Transform>,
+        Read<'s, Time>,
+        ReadExpect<'s, GameConfig>,
+    );
+
+    fn run(&mut self, (mut goals, markers, mut transforms, time, config): Self::SystemData) {
+        let configs = match &config.mode().goals {
+            Some(configs) => configs,
+            None => return,
+        };
+        self.elapsed += time.delta_seconds();
+
+        for (mouth, mouth_config) in goals.mouths.iter_mut().zip([&configs.left, &configs.right].iter()) {
+            let sway = match &mouth_config.motion {
+                Some(motion) if motion.period > 0.0 => {
+                    motion.range * (self.elapsed / motion.period * std::f32::consts::PI * 2.0).sin()
+                },
+                _ => 0.0,
+            };
+            let half = (mouth.height * 0.5).min(AREA_HEIGHT * 0.5);
+            mouth.center = (mouth_config.center + sway).max(half).min(AREA_HEIGHT - half);
+        }
+
+        for (marker, transform) in (&markers, &mut transforms).join() {
+            let mouth = goals.mouth(marker.side);
+            let x = match marker.side {
+                Side::Left => MARKER_WIDTH * 0.5,
+                Side::Right => AREA_WIDTH - MARKER_WIDTH * 0.5,
+            };
+            transform.set_translation_xyz(x, mouth.center, -0.1);
+            transform.set_scale(Vector3::new(MARKER_WIDTH / PADDLE_SPRITE_WIDTH, mouth.height / PADDLE_SPRITE_HEIGHT, 1.0));
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use amethyst::{
+    core::SystemDesc,
+    ecs::{ReadExpect, System, SystemData, World, Write, WriteStorage},
+    shrev::{EventChannel, ReaderId},
+    ui::UiText,
+};
+
+use crate::pong::{MatchRules, PongEvent, ScoreBoard, ScoreText, Side};
+
+#[derive(Default)]
+pub struct MatchEndSystemDesc;
+
+impl <'s, 'f> SystemDesc<'s, 'f, MatchEndSystem> for MatchEndSystemDesc {
+    fn build(self, world: &mut World) -> MatchEndSystem {
+        <MatchEndSystem as System<'_>>::SystemData::setup(world);
+        let reader_id = world.fetch_mut::<EventChannel<PongEvent>>().register_reader();
+        MatchEndSystem { reader_id }
+    }
+}
+
+/// Ends the match once a player reaches the points to win, then starts the next one from the starting board.
+pub struct MatchEndSystem {
+    reader_id: ReaderId<PongEvent>,
+}
+
+impl <'s> System<'s> for MatchEndSystem {
+    type SystemData = (
+        Write<'s, EventChannel<PongEvent>>,
+        Write<'s, ScoreBoard>,
+        ReadExpect<'s, MatchRules>,
+        ReadExpect<'s, ScoreText>,
+        WriteStorage<'s, UiText>,
+    );
+
+    fn run(&mut self, (mut pong_event_channel, mut scores, rules, score_text, mut ui_text): Self::SystemData) {
+        let scored = pong_event_channel
+            .read(&mut self.reader_id)
+            .filter_map(|event| match event {
+                PongEvent::Score { side, .. } => Some(*side),
+                _ => None,
+            })
+            .last();
+        let scorer = match scored {
+            Some(scorer) => scorer,
+            None => return,
+        };
+
+        let score = match scorer {
+            Side::Left => scores.score_left,
+            Side::Right => scores.score_right,
+        };
+        if score < rules.points_to_win as f32 {
+            return;
+        }
+
+        pong_event_channel.single_write(PongEvent::MatchWon(scorer));
+        *scores = rules.starting_board();
+        score_text.show(&mut ui_text, &scores);
+    }
+}
@@ -1,3 +1,8 @@
+use amethyst::{
+    core::{ArcThreadPool, SystemDesc},
+    ecs::{Dispatcher, DispatcherBuilder, World},
+};
+
 pub use self::paddle::PaddleSystem;
 pub use self::move_balls::MoveBallsSystem;
 pub use self::bounce::BounceSystem;
@@ -6,6 +11,16 @@ pub use self::input::InputSystem;
 pub use self::audio::AudioSystemDesc;
 pub use self::velocity::VelocitySystemDesc;
 pub use self::ball::BallSystemDesc;
+pub use self::collide_balls::BallCollisionSystem;
+pub use self::volume::VolumeSystemDesc;
+pub use self::music::MusicSystemDesc;
+pub use self::audio_guide::AudioGuideSystemDesc;
+pub use self::shrink::ShrinkSystemDesc;
+pub use self::serve::ServeSystem;
+pub use self::countdown::CountdownSystem;
+pub use self::dash::DashSystem;
+pub use self::goals::GoalSystem;
+pub use self::match_end::MatchEndSystemDesc;
 
 mod paddle;
 mod move_balls;
@@ -15,3 +30,40 @@ mod input;
 mod audio;
 mod velocity;
 mod ball;
+mod collide_balls;
+mod volume;
+mod music;
+mod audio_guide;
+mod shrink;
+mod serve;
+mod countdown;
+mod dash;
+mod goals;
+mod match_end;
+
+pub fn gameplay_dispatcher(world: &mut World) -> Dispatcher<'static, 'static> {
+    let velocity_system = VelocitySystemDesc::default().build(world);
+    let ball_system = BallSystemDesc::default().build(world);
+    let shrink_system = ShrinkSystemDesc::default().build(world);
+    let match_end_system = MatchEndSystemDesc::default().build(world);
+    let pool = (*world.read_resource::<ArcThreadPool>()).clone();
+
+    let mut dispatcher = DispatcherBuilder::new()
+        .with(DashSystem::default(), "dash_system", &[])
+        .with(PaddleSystem, "paddle_system", &["dash_system"])
+        .with(ServeSystem, "serve_system", &["paddle_system"])
+        .with(MoveBallsSystem, "move_ball_system", &["serve_system"])
+        .with(GoalSystem::default(), "goal_system", &[])
+        .with(BounceSystem, "bounce_system", &["paddle_system", "move_ball_system", "goal_system"])
+        .with(BallCollisionSystem, "ball_collision_system", &["bounce_system"])
+        .with(WinnerSystem, "winner_system", &["move_ball_system", "goal_system"])
+        .with(match_end_system, "match_end_system", &["winner_system"])
+        .with(velocity_system, "velocity_system", &["bounce_system", "move_ball_system"])
+        .with(ball_system, "ball_system", &["winner_system"])
+        .with(CountdownSystem::default(), "countdown_system", &["ball_system"])
+        .with(shrink_system, "shrink_system", &["bounce_system", "match_end_system"])
+        .with_pool(pool)
+        .build();
+    dispatcher.setup(world);
+    dispatcher
+}
@@ -4,9 +4,10 @@ use amethyst::{
         transform::Transform,
     },
     derive::SystemDesc,
-    ecs::{Join, Read, ReadStorage, SystemData, System, WriteStorage}
+    ecs::{Join, Read, ReadExpect, SystemData, System, WriteStorage}
 };
 
+use crate::config::GameConfig;
 use crate::pong::Ball;
 
 #[derive(SystemDesc)]
@@ -14,15 +15,33 @@ pub struct MoveBallsSystem;
 
 impl <'s> System <'s> for MoveBallsSystem {
     type SystemData = (
-        ReadStorage<'s, Ball>,
+        WriteStorage<'s, Ball>,
         WriteStorage<'s, Transform>,
         Read<'s, Time>,
+        ReadExpect<'s, GameConfig>,
     );
 
-    fn run(&mut self, (balls, mut locals, time): Self::SystemData) {
-        for (ball, local) in (&balls, &mut locals).join() {
-            local.prepend_translation_x(ball.velocity[0] * time.delta_seconds());
-            local.prepend_translation_y(ball.velocity[1] * time.delta_seconds());
+    fn run(&mut self, (mut balls, mut locals, time, config): Self::SystemData) {
+        let delta = time.delta_seconds();
+
+        for (ball, local) in (&mut balls, &mut locals).join() {
+            if ball.held.is_some() {
+                continue;
+            }
+
+            let (sin, cos) = (config.spin.magnus * ball.spin * delta).sin_cos();
+            let [x_velocity, y_velocity] = ball.velocity;
+            ball.velocity = [x_velocity * cos - y_velocity * sin, x_velocity * sin + y_velocity * cos];
+            ball.spin *= (-config.spin.decay * delta).exp();
+
+            if let Some(air_hockey) = &config.mode().air_hockey {
+                let damping = (-air_hockey.puck_friction * delta).exp();
+                ball.velocity = [ball.velocity[0] * damping, ball.velocity[1] * damping];
+            }
+
+            local.prepend_translation_x(ball.velocity[0] * delta);
+            local.prepend_translation_y(ball.velocity[1] * delta);
+            local.rotate_2d(ball.spin * delta);
         }
     }
 }
@@ -0,0 +1,178 @@
+use amethyst::{
+    assets::AssetStorage,
+    audio::{output::Output, AudioSink, Source, SourceHandle},
+    core::{SystemDesc, timing::Time},
+    ecs::{Read, System, SystemData, World, Write, WriteStorage},
+    input::{InputEvent, StringBindings},
+    shrev::{EventChannel, ReaderId},
+    ui::UiText,
+};
+
+use crate::audio::{Intensity, Music, PlaylistConfig};
+use crate::pong::{MatchRules, NowPlayingText, PongEvent, ScoreBoard};
+use crate::settings::Settings;
+
+const TOAST_SECONDS: f32 = 3.0;
+const TOAST_FADE_SECONDS: f32 = 0.5;
+
+#[derive(Default)]
+pub struct MusicSystemDesc;
+
+impl <'s, 'f> SystemDesc<'s, 'f, MusicSystem> for MusicSystemDesc {
+    fn build(self, world: &mut World) -> MusicSystem {
+        <MusicSystem as System<'_>>::SystemData::setup(world);
+        let input_reader = world.fetch_mut::<EventChannel<InputEvent<StringBindings>>>().register_reader();
+        let pong_reader = world.fetch_mut::<EventChannel<PongEvent>>().register_reader();
+
+        MusicSystem {
+            input_reader,
+            pong_reader,
+            current: None,
+            fading: None,
+            fade: 1.0,
+            queued: None,
+            rally_hits: 0,
+            duck_timer: 0.0,
+            toast_timer: 0.0,
+        }
+    }
+}
+
+pub struct MusicSystem {
+    input_reader: ReaderId<InputEvent<StringBindings>>,
+    pong_reader: ReaderId<PongEvent>,
+    current: Option<AudioSink>,
+    fading: Option<AudioSink>,
+    fade: f32,
+    queued: Option<(SourceHandle, bool)>,
+    rally_hits: u32,
+    duck_timer: f32,
+    toast_timer: f32,
+}
+
+impl MusicSystem {
+    fn start(&mut self, output: &Output, source: &Source, crossfade: bool) {
+        let sink = AudioSink::new(output);
+        if sink.append(source).is_err() {
+            return;
+        }
+
+        if crossfade {
+            self.fading = self.current.take();
+            self.fade = 0.0;
+        } else {
+            self.fading = None;
+            self.fade = 1.0;
+        }
+        self.current = Some(sink);
+    }
+}
+
+impl <'s> System<'s> for MusicSystem {
+    type SystemData = (
+        Read<'s, EventChannel<InputEvent<StringBindings>>>,
+        Read<'s, EventChannel<PongEvent>>,
+        Option<Write<'s, Music>>,
+        Option<Read<'s, Output>>,
+        Read<'s, AssetStorage<Source>>,
+        Read<'s, PlaylistConfig>,
+        Read<'s, Settings>,
+        Read<'s, ScoreBoard>,
+        Read<'s, MatchRules>,
+        Option<Read<'s, NowPlayingText>>,
+        WriteStorage<'s, UiText>,
+        Read<'s, Time>,
+    );
+
+    fn run(&mut self, (
+        input_events, pong_events, mut music, output, storage, playlist,
+        settings, scores, rules, now_playing, mut ui_text, time,
+    ): Self::SystemData) {
+        let delta = time.delta_seconds();
+        let mut crossfade_requested = false;
+
+        for event in input_events.read(&mut self.input_reader) {
+            if let InputEvent::ActionPressed(action) = event {
+                if action == "NextTrack" {
+                    if let Some(music) = music.as_mut() {
+                        music.skip();
+                    }
+                    crossfade_requested = true;
+                }
+            }
+        }
+
+        for event in pong_events.read(&mut self.pong_reader) {
+            match event {
+                PongEvent::Bounce { .. } => self.rally_hits += 1,
+                PongEvent::Score { .. } => {
+                    self.rally_hits = 0;
+                    self.duck_timer = playlist.duck_seconds;
+                },
+                PongEvent::MatchWon(_) => self.rally_hits = 0,
+                _ => {},
+            }
+        }
+
+        if let (Some(music), Some(output)) = (music.as_mut(), output.as_ref()) {
+            let match_point = (rules.points_to_win as f32 - 1.).max(1.);
+            let intensity = if scores.score_left >= match_point || scores.score_right >= match_point || self.rally_hits >= playlist.intense_rally_hits {
+                Intensity::Intense
+            } else {
+                Intensity::Calm
+            };
+
+            if intensity != music.intensity {
+                music.intensity = intensity;
+                crossfade_requested |= music.has_intense_tracks();
+            }
+
+            let finished = self.current.as_ref().map_or(true, |sink| sink.empty());
+            if (crossfade_requested || finished) && self.queued.is_none() {
+                self.queued = music.next().map(|handle| (handle, !finished));
+            }
+
+            if let Some((handle, crossfade)) = self.queued.take() {
+                // Let a running crossfade finish first so its outgoing track is not cut off.
+                let waiting = crossfade && self.fading.is_some();
+                match storage.get(&handle) {
+                    Some(source) if !waiting => self.start(output, source, crossfade && playlist.crossfade_seconds > 0.0),
+                    _ => self.queued = Some((handle, crossfade)),
+                }
+            }
+        }
+
+        if self.fading.is_some() {
+            self.fade = (self.fade + delta / playlist.crossfade_seconds).min(1.0);
+            if self.fade >= 1.0 {
+                self.fading = None;
+            }
+        }
+
+        self.duck_timer = (self.duck_timer - delta).max(0.0);
+        let duck_progress = if playlist.duck_seconds > 0.0 { self.duck_timer / playlist.duck_seconds } else { 0.0 };
+        let volume = settings.audio.music_volume() * (1.0 - (1.0 - playlist.duck_volume) * duck_progress);
+
+        if let Some(sink) = self.current.as_mut() {
+            sink.set_volume(volume * self.fade);
+        }
+        if let Some(sink) = self.fading.as_mut() {
+            sink.set_volume(volume * (1.0 - self.fade));
+        }
+
+        let text = match now_playing.and_then(|now_playing| ui_text.get_mut(now_playing.text)) {
+            Some(text) => text,
+            None => return,
+        };
+
+        if let Some(name) = music.as_mut().and_then(|music| music.announcement.take()) {
+            text.text = format!("Now playing: {}", name);
+            self.toast_timer = TOAST_SECONDS;
+        }
+
+        if self.toast_timer > 0.0 {
+            self.toast_timer = (self.toast_timer - delta).max(0.0);
+            text.color[3] = (self.toast_timer / TOAST_FADE_SECONDS).min(1.0);
+        }
+    }
+}
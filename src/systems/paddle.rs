@@ -1,11 +1,15 @@
 use amethyst::{
-    core::Transform,
+    core::{math::Point3, Transform, timing::Time},
     derive::SystemDesc,
-    ecs::{Join, Read, ReadStorage, System, SystemData, WriteStorage},
+    ecs::{Join, Read, ReadExpect, ReadStorage, System, SystemData, WriteStorage},
     input::{InputHandler, StringBindings},
+    renderer::Camera,
+    window::ScreenDimensions,
 };
 
-use crate::pong::{Paddle, Side, AREA_HEIGHT, PADDLE_HEIGHT};
+use crate::config::GameConfig;
+use crate::pong::{Dash, Paddle, Side, AREA_HEIGHT};
+use crate::settings::{InputDevice, Settings};
 
 #[derive(SystemDesc)]
 pub struct PaddleSystem;
@@ -13,26 +17,99 @@ pub struct PaddleSystem;
 impl <'s> System<'s> for PaddleSystem {
     type SystemData = (
         WriteStorage<'s, Transform>,
-        ReadStorage<'s, Paddle>,
+        WriteStorage<'s, Paddle>,
         Read<'s, InputHandler<StringBindings>>,
+        Read<'s, Time>,
+        ReadExpect<'s, GameConfig>,
+        Read<'s, Settings>,
+        ReadStorage<'s, Camera>,
+        ReadExpect<'s, ScreenDimensions>,
+        ReadStorage<'s, Dash>,
     );
 
-    fn run(&mut self, (mut transform, paddle, input): Self::SystemData) {
-        for (paddle, transform) in (&paddle, &mut transform).join() {
-            let movement = match paddle.side {
-                Side::Right => input.axis_value("right_paddle"),
-                Side::Left => input.axis_value("left_paddle"),
+    fn run(&mut self, (mut transform, mut paddles, input, time, config, settings, cameras, screen, dashes): Self::SystemData) {
+        let free = config.mode().air_hockey.is_some();
+        let config = &config.paddle;
+        let delta = time.delta_seconds();
+
+        let mouse = input.mouse_position().and_then(|(x, y)| {
+            let (camera, camera_transform) = (&cameras, &transform).join().next()?;
+            let world = camera.projection().screen_to_world_point(Point3::new(x, y, 0.0), screen.diagonal(), camera_transform);
+            Some([world.x, world.y])
+        });
+
+        for (paddle, transform, dash) in (&mut paddles, &mut transform, dashes.maybe()).join() {
+            let profile = settings.controls.profile(paddle.side);
+            let max_speed = config.max_speed * paddle.speed_multiplier;
+            let position = [transform.translation().x, transform.translation().y];
+            let device = settings.controls.device(paddle.side);
+            let movement = match (device, mouse) {
+                (InputDevice::Mouse, Some(target)) if delta > 0.0 => {
+                    // Ask for just enough speed to land on the cursor this frame, capped at full stick.
+                    let stick = |axis: usize| ((target[axis] - position[axis]) / (max_speed * delta)).max(-1.0).min(1.0);
+                    [if free { stick(0) } else { 0.0 }, stick(1)]
+                },
+                (InputDevice::Mouse, _) => [0.0, 0.0],
+                (InputDevice::Keyboard, _) => {
+                    let (x_axis, y_axis) = match paddle.side {
+                        Side::Right => ("right_paddle_x", "right_paddle"),
+                        Side::Left => ("left_paddle_x", "left_paddle"),
+                    };
+                    let raw_x = if free { input.axis_value(x_axis).unwrap_or(0.0) } else { 0.0 };
+                    let raw_y = input.axis_value(y_axis).unwrap_or(0.0);
+                    [
+                        profile.ramp(paddle.input[0], profile.shape(raw_x), delta),
+                        profile.ramp(paddle.input[1], profile.shape(raw_y), delta),
+                    ]
+                },
+            };
+            let movement = [
+                profile.smooth(paddle.input[0], movement[0], delta),
+                profile.smooth(paddle.input[1], movement[1], delta),
+            ];
+            paddle.input = movement;
+
+            let steer = |velocity: f32, movement: f32| {
+                let rate = if movement != 0.0 { config.acceleration } else { config.friction };
+                approach(velocity, movement * max_speed, rate * delta)
             };
-            if let Some(mv_amount) = movement {
-                if mv_amount != 0.0 {
-                    let scale_amount = 1.2 * mv_amount as f32;
-                    transform.set_translation_y(
-                        (transform.translation().y + scale_amount)
-                            .min(AREA_HEIGHT - PADDLE_HEIGHT * 0.5)
-                            .max(PADDLE_HEIGHT * 0.5)
-                    );
-                }
-            }
+            paddle.velocity = match dash {
+                Some(dash) if dash.remaining > 0.0 => {
+                    let speed = max_speed * config.dash.speed_multiplier;
+                    [dash.direction[0] * speed, dash.direction[1] * speed]
+                },
+                // The cursor stick already asks for exactly the speed needed, so accelerating and braking
+                // towards it would only overshoot the cursor.
+                _ if device == InputDevice::Mouse => [movement[0] * max_speed, movement[1] * max_speed],
+                _ => [steer(paddle.velocity[0], movement[0]), steer(paddle.velocity[1], movement[1])],
+            };
+
+            paddle.height = approach(paddle.height, paddle.target_height, config.resize_speed * delta);
+            transform.set_scale(paddle.scale());
+
+            let x_range = paddle.x_range(free);
+            let y_range = [paddle.height * 0.5, AREA_HEIGHT - paddle.height * 0.5];
+            let x = confine(position[0] + paddle.velocity[0] * delta, &mut paddle.velocity[0], x_range);
+            let y = confine(position[1] + paddle.velocity[1] * delta, &mut paddle.velocity[1], y_range);
+
+            transform.set_translation_x(x);
+            transform.set_translation_y(y);
         }
     }
 }
+
+/// Keeps a paddle coordinate inside `range`, stopping it dead when it reaches either end.
+fn confine(position: f32, velocity: &mut f32, range: [f32; 2]) -> f32 {
+    if position <= range[0] || position >= range[1] {
+        *velocity = 0.0;
+    }
+    position.min(range[1]).max(range[0])
+}
+
+fn approach(current: f32, target: f32, step: f32) -> f32 {
+    if current < target {
+        (current + step).min(target)
+    } else {
+        (current - step).max(target)
+    }
+}
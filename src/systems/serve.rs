@@ -0,0 +1,72 @@
+use amethyst::{
+    core::{timing::Time, Transform},
+    derive::SystemDesc,
+    ecs::{Join, Read, ReadExpect, ReadStorage, System, SystemData, Write, WriteStorage},
+    input::{InputHandler, StringBindings},
+    shrev::EventChannel,
+};
+
+use crate::config::GameConfig;
+use crate::pong::{Ball, Paddle, PongEvent, Side};
+
+#[derive(SystemDesc)]
+pub struct ServeSystem;
+
+impl <'s> System<'s> for ServeSystem {
+    type SystemData = (
+        WriteStorage<'s, Ball>,
+        WriteStorage<'s, Transform>,
+        ReadStorage<'s, Paddle>,
+        Read<'s, InputHandler<StringBindings>>,
+        Read<'s, Time>,
+        ReadExpect<'s, GameConfig>,
+        Write<'s, EventChannel<PongEvent>>,
+    );
+
+    fn run(&mut self, (mut balls, mut transforms, paddles, input, time, config, mut pong_event_channel): Self::SystemData) {
+        let delta = time.delta_seconds();
+        let paddle_positions = (&paddles, &transforms)
+            .join()
+            .map(|(paddle, transform)| (paddle, [transform.translation().x, transform.translation().y]))
+            .collect::<Vec<_>>();
+
+        for (ball, transform) in (&mut balls, &mut transforms).join() {
+            let (side, elapsed) = match ball.held.as_mut() {
+                Some(held) => {
+                    held.elapsed += delta;
+                    (held.side, held.elapsed)
+                },
+                None => continue,
+            };
+            let (paddle, paddle_position) = match paddle_positions.iter().find(|(paddle, _)| paddle.side == side) {
+                Some(found) => found,
+                None => continue,
+            };
+
+            let position = paddle.serve_position(*paddle_position, ball.radius);
+            transform.set_translation_x(position[0]);
+            transform.set_translation_y(position[1]);
+
+            let action = match side {
+                Side::Left => "ServeLeft",
+                Side::Right => "ServeRight",
+            };
+            if !input.action_is_down(action).unwrap_or(false) && elapsed < config.serve.timeout {
+                continue;
+            }
+
+            // Serving on the move angles the ball the way the paddle is heading.
+            let max_speed = config.paddle.max_speed * paddle.speed_multiplier;
+            let angle = (paddle.velocity[1] / max_speed).max(-1.0).min(1.0) * config.serve.max_angle.to_radians();
+            let direction = match side {
+                Side::Left => 1.0,
+                Side::Right => -1.0,
+            };
+            let speed = ball.speed();
+
+            ball.velocity = [direction * speed * angle.cos(), speed * angle.sin()];
+            ball.held = None;
+            pong_event_channel.single_write(PongEvent::Serve { position });
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use amethyst::{
+    core::SystemDesc,
+    ecs::{Join, Read, ReadExpect, System, SystemData, World, WriteStorage},
+    shrev::{EventChannel, ReaderId},
+};
+
+use crate::config::{GameConfig, ShrinkTrigger};
+use crate::pong::{Paddle, PongEvent};
+
+#[derive(Default)]
+pub struct ShrinkSystemDesc;
+
+impl <'s, 'f> SystemDesc<'s, 'f, ShrinkSystem> for ShrinkSystemDesc {
+    fn build(self, world: &mut World) -> ShrinkSystem {
+        <ShrinkSystem as System<'_>>::SystemData::setup(world);
+        let reader_id = world.fetch_mut::<EventChannel<PongEvent>>().register_reader();
+        ShrinkSystem { reader_id, match_hits: 0 }
+    }
+}
+
+pub struct ShrinkSystem {
+    reader_id: ReaderId<PongEvent>,
+    match_hits: u32,
+}
+
+impl <'s> System<'s> for ShrinkSystem {
+    type SystemData = (
+        Read<'s, EventChannel<PongEvent>>,
+        WriteStorage<'s, Paddle>,
+        ReadExpect<'s, GameConfig>,
+    );
+
+    fn run(&mut self, (events, mut paddles, config): Self::SystemData) {
+        let rule = config.mode().shrink.as_ref();
+
+        for event in events.read(&mut self.reader_id) {
+            let rule = match (rule, event) {
+                (_, PongEvent::MatchWon(_)) => {
+                    self.match_hits = 0;
+                    for paddle in (&mut paddles).join() {
+                        paddle.target_height = paddle.base_height;
+                    }
+                    continue;
+                },
+                (Some(rule), _) => rule,
+                (None, _) => continue,
+            };
+
+            let only_side = match (&rule.trigger, event) {
+                (ShrinkTrigger::OwnScore, PongEvent::Score { side, .. }) => Some(*side),
+                (ShrinkTrigger::MatchHits(every), PongEvent::Bounce { .. }) => {
+                    self.match_hits += 1;
+                    if self.match_hits % (*every).max(1) != 0 {
+                        continue;
+                    }
+                    None
+                },
+                _ => continue,
+            };
+
+            for paddle in (&mut paddles).join() {
+                if only_side.map_or(true, |side| side == paddle.side) {
+                    let min_height = rule.min_height.min(paddle.base_height);
+                    paddle.target_height = (paddle.target_height - rule.step).max(min_height);
+                }
+            }
+        }
+    }
+}
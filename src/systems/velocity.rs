@@ -1,10 +1,11 @@
 use amethyst::{
-    ecs::{Join, WriteStorage, Read, System, SystemData, World},
+    ecs::{Join, WriteStorage, Read, ReadExpect, System, SystemData, World},
     shrev::{EventChannel, ReaderId},
     core::SystemDesc,
 };
 
-use crate::pong::{PongEvent, Ball};
+use crate::config::GameConfig;
+use crate::pong::{PongEvent, Ball, MatchRules};
 
 #[derive(Default)]
 pub struct VelocitySystemDesc;
@@ -25,37 +26,34 @@ impl <'s> System<'s> for VelocitySystem {
     type SystemData = (
         Read<'s, EventChannel<PongEvent>>,
         WriteStorage<'s, Ball>,
+        ReadExpect<'s, GameConfig>,
+        ReadExpect<'s, MatchRules>,
     );
 
-    fn run(&mut self, (event_channel, mut balls): Self::SystemData) {
+    fn run(&mut self, (event_channel, mut balls, config, rules): Self::SystemData) {
+        let ball_speed = &config.mode().ball_speed;
+
         for event in event_channel.read(&mut self.reader_id) {
             match event {
-                PongEvent::Bounce(ball_id) => {
+                PongEvent::Bounce { ball: ball_id, .. } => {
                     for ball in (&mut balls).join() {
                         if &ball.id == ball_id {
-                            let (x_direction, y_direction) = get_ball_directions(ball);
-
-                            ball.velocity[0] = ball.velocity[0] + (5. * x_direction);
-                            ball.velocity[1] = ball.velocity[1] + (5. * y_direction);
+                            ball.hits += 1;
+                            let speed = ball_speed.speed_after(ball.hits, rules.speed_multiplier);
+                            // A puck keeps whatever speed the hit gave it, up to the curve.
+                            let speed = if config.mode().air_hockey.is_some() { ball.speed().min(speed) } else { speed };
+                            ball.set_speed(speed);
                         }
                     }
                 },
-                PongEvent::Score => {
+                PongEvent::Score { .. } => {
                     for ball in (&mut balls).join() {
-                        let (x_direction, y_direction) = get_ball_directions(ball);
-
-                        ball.velocity[0] = ball.original_velocity[0] * x_direction;
-                        ball.velocity[1] = ball.original_velocity[1] * y_direction;
+                        ball.hits = 0;
+                        ball.set_speed(ball_speed.speed_after(0, rules.speed_multiplier));
                     }
                 },
+                _ => {},
             }
         }
     }
 }
-
-fn get_ball_directions(ball: &Ball) -> (f32, f32) {
-    let x_direction = if ball.velocity[0] > 0. { 1. } else { -1. };
-    let y_direction = if ball.velocity[1] > 0. { 1. } else { -1. };
-
-    (x_direction, y_direction)
-}
@@ -0,0 +1,57 @@
+use amethyst::{
+    ecs::{Read, ReadExpect, System, SystemData, World, Write},
+    input::{InputEvent, StringBindings},
+    shrev::{EventChannel, ReaderId},
+    core::SystemDesc,
+};
+
+use crate::settings::{Settings, UserFiles};
+
+const VOLUME_STEP: f32 = 0.1;
+
+#[derive(Default)]
+pub struct VolumeSystemDesc;
+
+impl <'s, 'f> SystemDesc<'s, 'f, VolumeSystem> for VolumeSystemDesc {
+    fn build(self, world: &mut World) -> VolumeSystem {
+        <VolumeSystem as System<'_>>::SystemData::setup(world);
+        let reader_id = world.fetch_mut::<EventChannel<InputEvent<StringBindings>>>().register_reader();
+        VolumeSystem { reader_id }
+    }
+}
+
+pub struct VolumeSystem {
+    reader_id: ReaderId<InputEvent<StringBindings>>,
+}
+
+impl <'s> System<'s> for VolumeSystem {
+    type SystemData = (
+        Read<'s, EventChannel<InputEvent<StringBindings>>>,
+        Write<'s, Settings>,
+        ReadExpect<'s, UserFiles>,
+    );
+
+    fn run(&mut self, (input_events, mut settings, user_files): Self::SystemData) {
+        let mut changed = false;
+
+        for event in input_events.read(&mut self.reader_id) {
+            if let InputEvent::ActionPressed(action) = event {
+                let Settings { audio, accessibility, .. } = &mut *settings;
+                changed |= match action.as_str() {
+                    "VolumeUp" => { audio.master = (audio.master + VOLUME_STEP).min(1.0); true },
+                    "VolumeDown" => { audio.master = (audio.master - VOLUME_STEP).max(0.0); true },
+                    "MuteMusic" => { audio.music_muted = !audio.music_muted; true },
+                    "MuteSfx" => { audio.sfx_muted = !audio.sfx_muted; true },
+                    "ToggleAudioGuide" => { accessibility.audio_guide = !accessibility.audio_guide; true },
+                    _ => false,
+                };
+            }
+        }
+
+        if changed {
+            if let Err(error) = settings.save(&user_files.settings) {
+                log::error!("Could not save settings: {}", error);
+            }
+        }
+    }
+}
@@ -1,13 +1,12 @@
 use amethyst::{
     core::transform::Transform,
     derive::SystemDesc,
-    ecs::{Join, System, SystemData, WriteStorage, Write, ReadExpect},
+    ecs::{Join, Read, System, SystemData, WriteStorage, Write, ReadExpect},
     ui::UiText,
     shrev::EventChannel,
 };
 
-use crate::audio::SoundEvent;
-use crate::pong::{PongEvent, Ball, ScoreBoard, ScoreText, AREA_WIDTH};
+use crate::pong::{PongEvent, Ball, GoalMouths, ScoreBoard, ScoreText, Side, AREA_WIDTH};
 
 #[derive(SystemDesc)]
 pub struct WinnerSystem;
@@ -19,36 +18,33 @@ impl<'s> System<'s> for WinnerSystem {
         WriteStorage<'s, UiText>,
         Write<'s, ScoreBoard>,
         ReadExpect<'s, ScoreText>,
-        Write<'s, EventChannel<SoundEvent>>,
+        Read<'s, GoalMouths>,
         Write<'s, EventChannel<PongEvent>>,
     );
 
-    fn run(&mut self, (mut balls, mut locals, mut ui_text, mut scores, score_text, mut event_channel, mut pong_event_channel): Self::SystemData) {
+    fn run(&mut self, (mut balls, mut locals, mut ui_text, mut scores, score_text, goals, mut pong_event_channel): Self::SystemData) {
         for (ball, transform) in (&mut balls, &mut locals).join() {
             let ball_x = transform.translation().x;
+            let ball_y = transform.translation().y;
 
-            let did_hit = if ball_x <= ball.radius {
-                scores.score_right = (scores.score_right + 1.).min(999.);
-
-                if let Some(text) = ui_text.get_mut(score_text.p2_score) {
-                    text.text = scores.score_right.to_string();
-                }
-                true
-            } else if ball_x >= AREA_WIDTH - ball.radius {
-                scores.score_left = (scores.score_left + 1.).min(999.);
-
-                if let Some(text) = ui_text.get_mut(score_text.p1_score) {
-                    text.text = scores.score_left.to_string();
-                }
-                true
+            // A ball only scores once it is through the mouth of the goal it reached.
+            let scorer = if ball_x <= ball.radius && goals.contains(Side::Left, ball_y) {
+                Side::Right
+            } else if ball_x >= AREA_WIDTH - ball.radius && goals.contains(Side::Right, ball_y) {
+                Side::Left
             } else {
-                false
+                continue;
+            };
+
+            let score = match scorer {
+                Side::Left => &mut scores.score_left,
+                Side::Right => &mut scores.score_right,
             };
+            *score = (*score + 1.).min(999.);
 
-            if did_hit {
-                event_channel.single_write(SoundEvent::Score);
-                pong_event_channel.single_write(PongEvent::Score);
-            }
+            let position = [ball_x, ball_y];
+            pong_event_channel.single_write(PongEvent::Score { side: scorer, position, speed: ball.speed() });
+            score_text.show(&mut ui_text, &scores);
         }
     }
 }